@@ -0,0 +1,311 @@
+//! A Histogram that keeps user metadata next to the count of every category.
+
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+use core::iter;
+
+use hashbrown::{hash_map, HashMap};
+
+use crate::{Count, DefaultHashBuilder, Histogram, OverflowPolicy};
+
+/// A Histogram that stores metadata `M` for every `key`, like where it was first seen
+///
+/// The metadata is created by a callback when a `key` occurs for the first time, and can be
+/// updated by another callback on every further occurrence. As counts and metadata live in the
+/// same map, removing or pruning categories can never leave them out of sync.
+///
+/// # Example
+/// ```rust
+/// use histongram::AnnotatedHistogram;
+///
+/// let text = "the cat saw the dog";
+///
+/// // Remember the position of the first occurrence of every word
+/// let mut words = AnnotatedHistogram::<String, usize>::new();
+/// for (pos, word) in text.split(' ').enumerate() {
+///     words.add_ref(word, || pos);
+/// }
+///
+/// assert_eq!(words.count("the"), 2);
+/// assert_eq!(words.metadata("the"), Some(&0));
+/// assert_eq!(words.metadata("dog"), Some(&4));
+///
+/// words.prune_below(2);
+/// assert_eq!(words.metadata("dog"), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AnnotatedHistogram<K, M, S = DefaultHashBuilder, C = usize>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    C: Count,
+{
+    map: HashMap<K, (C, M), S>,
+    overflow: OverflowPolicy,
+}
+
+impl<K: Hash + Eq, M> AnnotatedHistogram<K, M> {
+    /// Create a new empty `AnnotatedHistogram`
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<K: Hash + Eq, M, S: BuildHasher> AnnotatedHistogram<K, M, S> {
+    /// Create a new empty `AnnotatedHistogram` using the given `hash_builder`
+    #[must_use]
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            map: HashMap::with_hasher(hash_builder),
+            overflow: OverflowPolicy::Panic,
+        }
+    }
+}
+
+impl<K, M, S, C> Default for AnnotatedHistogram<K, M, S, C>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+    C: Count,
+{
+    fn default() -> Self {
+        Self {
+            map: HashMap::default(),
+            overflow: OverflowPolicy::Panic,
+        }
+    }
+}
+
+impl<K: Hash + Eq, M, S: BuildHasher, C: Count> AnnotatedHistogram<K, M, S, C> {
+    /// Add a new occurrence of `key`, creating its metadata with `first` if it is new
+    ///
+    /// The metadata of `key`s that were seen before is left untouched.
+    ///
+    /// # Panics
+    /// If the count of `key` overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    pub fn add_ref<'a, Q, F>(&mut self, val: &'a Q, first: F)
+    where
+        K: Borrow<Q> + From<&'a Q>,
+        Q: ?Sized + Hash + Eq,
+        F: FnOnce() -> M,
+    {
+        self.add_ref_with(val, first, |_meta| {});
+    }
+
+    /// Add a new occurrence of `key`, creating its metadata with `first` if it is new and
+    /// updating it with `again` otherwise
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::AnnotatedHistogram;
+    ///
+    /// // Remember the last line every word was seen in
+    /// let mut words = AnnotatedHistogram::<String, usize>::new();
+    /// for (line, word) in [(1, "a"), (2, "b"), (3, "a")] {
+    ///     words.add_ref_with(word, || line, |last| *last = line);
+    /// }
+    ///
+    /// assert_eq!(words.get("a"), Some((2, &3)));
+    /// assert_eq!(words.get("b"), Some((1, &2)));
+    /// ```
+    ///
+    /// # Panics
+    /// If the count of `key` overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    pub fn add_ref_with<'a, Q, F, U>(&mut self, val: &'a Q, first: F, again: U)
+    where
+        K: Borrow<Q> + From<&'a Q>,
+        Q: ?Sized + Hash + Eq,
+        F: FnOnce() -> M,
+        U: FnOnce(&mut M),
+    {
+        if let Some((cnt, meta)) = self.map.get_mut(val) {
+            self.overflow.add(cnt, C::ONE);
+            again(meta);
+        } else {
+            self.map.insert(val.into(), (C::ONE, first()));
+        }
+    }
+
+    /// Add a new occurrence of `key` where ownership of the key moves to the Histogram, see
+    /// [`AnnotatedHistogram::add_ref()`]
+    ///
+    /// # Panics
+    /// If the count of `key` overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    pub fn add_owned<F>(&mut self, val: K, first: F)
+    where
+        F: FnOnce() -> M,
+    {
+        self.add_owned_with(val, first, |_meta| {});
+    }
+
+    /// Add a new occurrence of `key` where ownership of the key moves to the Histogram, see
+    /// [`AnnotatedHistogram::add_ref_with()`]
+    ///
+    /// # Panics
+    /// If the count of `key` overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    pub fn add_owned_with<F, U>(&mut self, val: K, first: F, again: U)
+    where
+        F: FnOnce() -> M,
+        U: FnOnce(&mut M),
+    {
+        if let Some((cnt, meta)) = self.map.get_mut(&val) {
+            self.overflow.add(cnt, C::ONE);
+            again(meta);
+        } else {
+            self.map.insert(val, (C::ONE, first()));
+        }
+    }
+
+    /// Add all counts of `other` to `self`, merging the metadata of `key`s present in both with
+    /// `combine`
+    ///
+    /// `combine` gets the metadata of `self` to update and the one of `other`.
+    ///
+    /// # Panics
+    /// If a count overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    pub fn append<F>(&mut self, other: Self, mut combine: F)
+    where
+        F: FnMut(&mut M, M),
+    {
+        for (key, (other_cnt, other_meta)) in other.map {
+            if let Some((cnt, meta)) = self.map.get_mut(&key) {
+                self.overflow.add(cnt, other_cnt);
+                combine(meta, other_meta);
+            } else {
+                self.map.insert(key, (other_cnt, other_meta));
+            }
+        }
+    }
+
+    /// Get the [`OverflowPolicy`] used when counts get too large
+    #[must_use]
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow
+    }
+
+    /// Set the [`OverflowPolicy`] used when counts get too large
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.overflow = policy;
+    }
+
+    /// Get the number of times `key` was added, which is `0` for absent `key`s
+    pub fn count<Q>(&self, key: &Q) -> C
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        self.map.get(key).map_or(C::ZERO, |&(cnt, _)| cnt)
+    }
+
+    /// Get the metadata of `key`, or `None` if it never occurred
+    pub fn metadata<Q>(&self, key: &Q) -> Option<&M>
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        self.map.get(key).map(|(_, meta)| meta)
+    }
+
+    /// Get mutable access to the metadata of `key`, or `None` if it never occurred
+    pub fn metadata_mut<Q>(&mut self, key: &Q) -> Option<&mut M>
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        self.map.get_mut(key).map(|(_, meta)| meta)
+    }
+
+    /// Get the count and the metadata of `key`, or `None` if it never occurred
+    pub fn get<Q>(&self, key: &Q) -> Option<(C, &M)>
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        self.map.get(key).map(|(cnt, meta)| (*cnt, meta))
+    }
+
+    /// Remove the category `key`, returning its count and metadata if it was present
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<(C, M)>
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        self.map.remove(key)
+    }
+
+    /// Only keep the categories for which `keep` returns `true`
+    ///
+    /// `keep` gets passed each `key` together with its count and metadata.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&K, C, &M) -> bool,
+    {
+        self.map.retain(|key, (cnt, meta)| keep(key, *cnt, meta));
+    }
+
+    /// Remove all categories that occurred less than `min_count` times, together with their
+    /// metadata
+    pub fn prune_below(&mut self, min_count: C) {
+        self.retain(|_key, cnt, _meta| cnt >= min_count);
+    }
+
+    /// Number of different `key`s
+    #[must_use]
+    pub fn num_categories(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Sum of all counts
+    ///
+    /// # Panics
+    /// If the total overflows `C` and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    #[must_use]
+    pub fn num_instances(&self) -> C {
+        self.overflow.sum(self.map.values().map(|&(cnt, _)| cnt))
+    }
+
+    /// Check if nothing was counted so far
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Iterate over all `key`s, their counts and metadata in arbitrary order
+    pub fn iter(&self) -> impl Iterator<Item = (&K, C, &M)> {
+        self.map.iter().map(|(key, (cnt, meta))| (key, *cnt, meta))
+    }
+
+    /// Drop the metadata and keep only the counts, using the same hasher
+    #[must_use]
+    pub fn into_histogram(self) -> Histogram<K, S, C>
+    where
+        S: Clone,
+    {
+        let mut map = HashMap::with_capacity_and_hasher(self.map.len(), self.map.hasher().clone());
+        map.extend(self.map.into_iter().map(|(key, (cnt, _))| (key, cnt)));
+        Histogram {
+            map,
+            overflow: self.overflow,
+        }
+    }
+}
+
+impl<K, M, S, C> IntoIterator for AnnotatedHistogram<K, M, S, C>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    C: Count,
+{
+    type Item = (K, C, M);
+    type IntoIter = iter::Map<hash_map::IntoIter<K, (C, M)>, fn((K, (C, M))) -> Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        // This can not be a closure as we need to name its type in `IntoIter`
+        fn flatten<K, C, M>((key, (cnt, meta)): (K, (C, M))) -> (K, C, M) {
+            (key, cnt, meta)
+        }
+
+        self.map.into_iter().map(flatten)
+    }
+}
@@ -0,0 +1,90 @@
+//! Generating random [`Histogram`]s for property based tests with [`proptest`].
+
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash};
+
+use proptest::arbitrary::{any, Arbitrary};
+use proptest::collection::{hash_map, SizeRange};
+use proptest::strategy::{BoxedStrategy, Strategy};
+
+use crate::{Count, Histogram};
+
+/// Maximum number of categories of Histograms created by [`Arbitrary`]
+const MAX_CATEGORIES: usize = 64;
+
+/// A [`Strategy`] for Histograms with `key`s from `keys`, counts from `counts` and a number of
+/// categories in `categories`
+///
+/// Every `key` is only drawn once, so its count is taken directly from `counts`. Counts that are
+/// not positive are left out, as a Histogram only contains `key`s that occurred. So keep the
+/// counts small enough that their sum still fits `C`, otherwise [`Histogram::num_instances()`]
+/// panics under the default [`OverflowPolicy`](crate::OverflowPolicy).
+///
+/// # Example
+/// ```rust
+/// use histongram::{arb_histogram, DefaultHashBuilder, Histogram};
+/// use proptest::prelude::*;
+///
+/// type Hist = Histogram<u8, DefaultHashBuilder, u32>;
+///
+/// fn hist() -> impl Strategy<Value = Hist> {
+///     arb_histogram(any::<u8>(), 1..100_u32, 0..10)
+/// }
+///
+/// proptest! {
+///     fn appending_adds_up(
+///         a in hist(),
+///         b in hist(),
+///     ) {
+///         let total = a.num_instances() + b.num_instances();
+///         let mut merged = a;
+///         merged.append(b);
+///         prop_assert_eq!(merged.num_instances(), total);
+///     }
+/// }
+/// # appending_adds_up();
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+pub fn arb_histogram<K, S, C>(
+    keys: impl Strategy<Value = K>,
+    counts: impl Strategy<Value = C>,
+    categories: impl Into<SizeRange>,
+) -> impl Strategy<Value = Histogram<K, S, C>>
+where
+    K: Hash + Eq + Debug,
+    S: BuildHasher + Default + Debug,
+    C: Count,
+{
+    hash_map(keys, counts, categories)
+        .prop_map(|map| map.into_iter().filter(|&(_, cnt)| cnt > C::ZERO).collect())
+}
+
+macro_rules! impl_arbitrary {
+    ($($ty:ty => $counts:expr),*) => {$(
+        /// Histograms with up to 64 categories and counts small enough to never overflow
+        #[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+        impl<K, S> Arbitrary for Histogram<K, S, $ty>
+        where
+            K: Arbitrary + Hash + Eq + 'static,
+            S: BuildHasher + Default + Debug + 'static,
+        {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with((): ()) -> Self::Strategy {
+                arb_histogram(any::<K>(), $counts, 0..=MAX_CATEGORIES).boxed()
+            }
+        }
+    )*};
+}
+
+impl_arbitrary!(
+    u8 => 1..=3_u8,
+    u16 => 1..=1000_u16,
+    u32 => 1..=1000_u32,
+    u64 => 1..=1000_u64,
+    u128 => 1..=1000_u128,
+    usize => 1..=1000_usize,
+    f32 => 0.001_f32..1000.0,
+    f64 => 0.001_f64..1000.0
+);
@@ -0,0 +1,75 @@
+//! Querying [`Histogram`]s archived with [`rkyv`] without deserializing them first.
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+
+use rkyv::Archive;
+
+use crate::{ArchivedHistogram, Count, OverflowPolicy};
+
+/// Lookups directly on the archived bytes, e.g. of a memory mapped file
+///
+/// With the `rkyv` feature a [`Histogram`](crate::Histogram) can be serialized with
+/// [`rkyv::to_bytes()`] and accessed with [`rkyv::access()`] in constant time, no matter how
+/// large it is. The counts are archived types like `u32_le`, use their `to_native()` method to
+/// get a regular number. Note that `usize` counts are archived as 32 bit numbers by default.
+///
+/// # Example
+/// ```rust
+/// use histongram::{ArchivedHistogram, DefaultHashBuilder, Histogram};
+/// use rkyv::rancor::Error;
+///
+/// let hist: Histogram<String> = ["a", "b", "a"].into_iter().collect();
+/// let bytes = rkyv::to_bytes::<Error>(&hist).unwrap();
+///
+/// type Archived = ArchivedHistogram<String, DefaultHashBuilder, usize>;
+/// let archived = rkyv::access::<Archived, Error>(&bytes).unwrap();
+/// assert_eq!(archived.get("a").map(|cnt| cnt.to_native()), Some(2));
+/// assert_eq!(archived.num_categories(), 2);
+///
+/// let loaded: Histogram<String> = rkyv::deserialize::<_, Error>(archived).unwrap();
+/// assert_eq!(loaded.count("b"), 1);
+/// ```
+impl<K, S, C> ArchivedHistogram<K, S, C>
+where
+    K: Hash + Eq + Archive,
+    K::Archived: Hash + Eq,
+    S: BuildHasher,
+    C: Count + Archive,
+    OverflowPolicy: Archive,
+{
+    /// The archived count of `key`, or `None` if it never occurred
+    pub fn get<Q>(&self, key: &Q) -> Option<&C::Archived>
+    where
+        K::Archived: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.get(key)
+    }
+
+    /// Check if `key` occurred at least once
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K::Archived: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Number of different `key`s
+    #[must_use]
+    pub fn num_categories(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Check if the archived Histogram is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Iterate over all archived `key`s and their counts in arbitrary order
+    pub fn iter(&self) -> impl Iterator<Item = (&K::Archived, &C::Archived)> {
+        self.map.iter()
+    }
+}
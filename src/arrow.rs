@@ -0,0 +1,121 @@
+//! Export of [`Histogram`]s to Apache Arrow and Parquet.
+
+use std::hash::{BuildHasher, Hash};
+use std::sync::Arc;
+
+use arrow_array::{
+    ArrayRef, Float32Array, Float64Array, RecordBatch, StringArray, StructArray, UInt16Array,
+    UInt32Array, UInt64Array, UInt8Array,
+};
+use arrow_schema::{DataType, Field};
+
+use crate::{Count, Histogram};
+
+/// A count type that can be stored in an Arrow column by [`Histogram::to_arrow()`]
+///
+/// This is implemented for all built-in count types except `u128`, which Arrow has no type for.
+/// `usize` is stored as `UInt64`.
+#[cfg_attr(docsrs, doc(cfg(feature = "arrow")))]
+pub trait ArrowCount: Count {
+    /// Turn `counts` into an Arrow array without nulls
+    fn into_array(counts: Vec<Self>) -> ArrayRef;
+}
+
+macro_rules! impl_arrow_count {
+    ($($ty:ty => $array:ty),*) => {$(
+        impl ArrowCount for $ty {
+            fn into_array(counts: Vec<Self>) -> ArrayRef {
+                Arc::new(<$array>::from(counts))
+            }
+        }
+    )*};
+}
+
+impl_arrow_count!(
+    u8 => UInt8Array,
+    u16 => UInt16Array,
+    u32 => UInt32Array,
+    u64 => UInt64Array,
+    f32 => Float32Array,
+    f64 => Float64Array
+);
+
+impl ArrowCount for usize {
+    fn into_array(counts: Vec<Self>) -> ArrayRef {
+        // `usize` is at most 64 bits wide on all supported platforms
+        Arc::new(UInt64Array::from_iter_values(
+            counts.into_iter().map(|cnt| cnt as u64),
+        ))
+    }
+}
+
+impl<K, S, C> Histogram<K, S, C>
+where
+    K: Hash + Eq + AsRef<str>,
+    S: BuildHasher,
+    C: ArrowCount,
+{
+    /// Convert the Histogram into an Arrow [`RecordBatch`] with a `key` and a `count` column
+    ///
+    /// The `key` column is `Utf8`, the type of the `count` column depends on `C`, see
+    /// [`ArrowCount`]. Neither column contains nulls. The rows are in arbitrary order.
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::Histogram;
+    /// let hist: Histogram<String> = ["a", "b", "a"].into_iter().collect();
+    ///
+    /// let batch = hist.to_arrow();
+    /// assert_eq!(batch.num_rows(), 2);
+    /// assert_eq!(batch.schema().field(0).name(), "key");
+    /// assert_eq!(batch.schema().field(1).name(), "count");
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "arrow")))]
+    #[must_use]
+    pub fn to_arrow(&self) -> RecordBatch {
+        let (keys, counts): (Vec<&str>, Vec<C>) = self
+            .map
+            .iter()
+            .map(|(key, cnt)| (key.as_ref(), *cnt))
+            .unzip();
+
+        let keys: ArrayRef = Arc::new(StringArray::from(keys));
+        let counts = C::into_array(counts);
+        let columns = vec![
+            (Arc::new(Field::new("key", DataType::Utf8, false)), keys),
+            (
+                Arc::new(Field::new("count", counts.data_type().clone(), false)),
+                counts,
+            ),
+        ];
+        StructArray::from(columns).into()
+    }
+
+    /// Write the Histogram to `w` as a Parquet file with the columns of
+    /// [`Histogram::to_arrow()`]
+    ///
+    /// # Errors
+    /// If writing to `w` fails.
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::Histogram;
+    /// let hist: Histogram<String> = ["a", "b", "a"].into_iter().collect();
+    ///
+    /// let mut buf = Vec::new();
+    /// hist.write_parquet(&mut buf).unwrap();
+    /// assert_eq!(&buf[..4], b"PAR1");
+    /// ```
+    #[cfg(feature = "parquet")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "parquet")))]
+    pub fn write_parquet<W>(&self, w: W) -> Result<(), parquet::errors::ParquetError>
+    where
+        W: std::io::Write + Send,
+    {
+        let batch = self.to_arrow();
+        let mut writer = parquet::arrow::ArrowWriter::try_new(w, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,346 @@
+//! A compact binary format to save and load [`Histogram`]s.
+
+use std::cmp::Ordering;
+use std::hash::{BuildHasher, Hash};
+use std::io::{self, Read, Write};
+
+use crate::{Count, Histogram};
+
+const MAGIC: &[u8; 4] = b"HGRM";
+const VERSION: u8 = 1;
+
+/// A type that can be written to and read from the binary format of
+/// [`Histogram::write_to()`] and [`Histogram::read_from()`]
+///
+/// The format starts with the magic bytes `HGRM` and a version byte, followed by the number of
+/// categories and then every `key` directly followed by its count. Every `key` occurs only once and
+/// all counts are positive. Integers are stored as LEB128 variable length integers, so small
+/// counts only take a single byte.
+///
+/// This is implemented for the integer types except `i128`, the floating point types, `bool`,
+/// `char`, [`String`], `Box<str>`, `Vec<u8>` and tuples of up to four of them, so n-grams stored
+/// as tuples can be saved as well. Implement it for your own `key` types by writing their fields
+/// one after another.
+///
+/// # Example
+/// ```rust
+/// use std::io::{self, Read, Write};
+/// use histongram::BinaryFormat;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Bigram(String, String);
+///
+/// impl BinaryFormat for Bigram {
+///     fn write_binary<W: Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+///         self.0.write_binary(w)?;
+///         self.1.write_binary(w)
+///     }
+///
+///     fn read_binary<R: Read + ?Sized>(r: &mut R) -> io::Result<Self> {
+///         Ok(Bigram(String::read_binary(r)?, String::read_binary(r)?))
+///     }
+/// }
+///
+/// let mut buf = Vec::new();
+/// Bigram("a".into(), "b".into()).write_binary(&mut buf).unwrap();
+/// assert_eq!(Bigram::read_binary(&mut &buf[..]).unwrap(), Bigram("a".into(), "b".into()));
+/// ```
+pub trait BinaryFormat: Sized {
+    /// Write `self` to `w`
+    ///
+    /// # Errors
+    /// If writing to `w` fails.
+    fn write_binary<W: Write + ?Sized>(&self, w: &mut W) -> io::Result<()>;
+
+    /// Read a value written by [`BinaryFormat::write_binary()`] from `r`
+    ///
+    /// # Errors
+    /// If reading from `r` fails, or the data is not a valid `Self`.
+    fn read_binary<R: Read + ?Sized>(r: &mut R) -> io::Result<Self>;
+}
+
+macro_rules! impl_binary_for_unsigned {
+    ($($ty:ty),*) => {$(
+        impl BinaryFormat for $ty {
+            fn write_binary<W: Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+                write_varint(w, u128::from(*self))
+            }
+
+            fn read_binary<R: Read + ?Sized>(r: &mut R) -> io::Result<Self> {
+                <$ty>::try_from(read_varint(r)?).map_err(invalid_data)
+            }
+        }
+    )*};
+}
+
+impl_binary_for_unsigned!(u8, u16, u32, u64, u128);
+
+impl BinaryFormat for usize {
+    fn write_binary<W: Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        // `usize` is at most 64 bits wide on all supported platforms
+        write_varint(w, *self as u128)
+    }
+
+    fn read_binary<R: Read + ?Sized>(r: &mut R) -> io::Result<Self> {
+        usize::try_from(read_varint(r)?).map_err(invalid_data)
+    }
+}
+
+macro_rules! impl_binary_for_signed {
+    ($($ty:ty),*) => {$(
+        impl BinaryFormat for $ty {
+            fn write_binary<W: Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+                // Zigzag encoding keeps small negative numbers small
+                #[allow(clippy::cast_sign_loss)]
+                let zigzag = ((i128::from(*self) << 1) ^ (i128::from(*self) >> 127)) as u128;
+                write_varint(w, zigzag)
+            }
+
+            fn read_binary<R: Read + ?Sized>(r: &mut R) -> io::Result<Self> {
+                let zigzag = read_varint(r)?;
+                #[allow(clippy::cast_possible_wrap)]
+                let value = (zigzag >> 1) as i128 ^ -((zigzag & 1) as i128);
+                <$ty>::try_from(value).map_err(invalid_data)
+            }
+        }
+    )*};
+}
+
+impl_binary_for_signed!(i8, i16, i32, i64);
+
+macro_rules! impl_binary_for_float {
+    ($($ty:ty),*) => {$(
+        impl BinaryFormat for $ty {
+            fn write_binary<W: Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+                w.write_all(&self.to_le_bytes())
+            }
+
+            fn read_binary<R: Read + ?Sized>(r: &mut R) -> io::Result<Self> {
+                let mut bytes = [0; std::mem::size_of::<$ty>()];
+                r.read_exact(&mut bytes)?;
+                Ok(<$ty>::from_le_bytes(bytes))
+            }
+        }
+    )*};
+}
+
+impl_binary_for_float!(f32, f64);
+
+impl BinaryFormat for bool {
+    fn write_binary<W: Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&[u8::from(*self)])
+    }
+
+    fn read_binary<R: Read + ?Sized>(r: &mut R) -> io::Result<Self> {
+        match u8::read_binary(r)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(invalid_data(format!("invalid bool {other}"))),
+        }
+    }
+}
+
+impl BinaryFormat for char {
+    fn write_binary<W: Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        u32::from(*self).write_binary(w)
+    }
+
+    fn read_binary<R: Read + ?Sized>(r: &mut R) -> io::Result<Self> {
+        let code = u32::read_binary(r)?;
+        char::from_u32(code).ok_or_else(|| invalid_data(format!("invalid char {code:#x}")))
+    }
+}
+
+impl BinaryFormat for Vec<u8> {
+    fn write_binary<W: Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        self.len().write_binary(w)?;
+        w.write_all(self)
+    }
+
+    fn read_binary<R: Read + ?Sized>(r: &mut R) -> io::Result<Self> {
+        let len = usize::read_binary(r)?;
+        let mut bytes = Vec::new();
+        // Do not trust `len` for the allocation, the data could be truncated or malicious
+        Read::take(&mut *r, len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(bytes)
+    }
+}
+
+impl BinaryFormat for String {
+    fn write_binary<W: Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        self.len().write_binary(w)?;
+        w.write_all(self.as_bytes())
+    }
+
+    fn read_binary<R: Read + ?Sized>(r: &mut R) -> io::Result<Self> {
+        String::from_utf8(Vec::read_binary(r)?).map_err(invalid_data)
+    }
+}
+
+impl BinaryFormat for Box<str> {
+    fn write_binary<W: Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+        self.len().write_binary(w)?;
+        w.write_all(self.as_bytes())
+    }
+
+    fn read_binary<R: Read + ?Sized>(r: &mut R) -> io::Result<Self> {
+        String::read_binary(r).map(String::into_boxed_str)
+    }
+}
+
+macro_rules! impl_binary_for_tuple {
+    ($($name:ident),*) => {
+        impl<$($name: BinaryFormat),*> BinaryFormat for ($($name,)*) {
+            #[allow(non_snake_case)]
+            fn write_binary<W: Write + ?Sized>(&self, w: &mut W) -> io::Result<()> {
+                let ($($name,)*) = self;
+                $($name.write_binary(w)?;)*
+                Ok(())
+            }
+
+            fn read_binary<R: Read + ?Sized>(r: &mut R) -> io::Result<Self> {
+                Ok(($($name::read_binary(r)?,)*))
+            }
+        }
+    };
+}
+
+impl_binary_for_tuple!(A, B);
+impl_binary_for_tuple!(A, B, C);
+impl_binary_for_tuple!(A, B, C, D);
+
+impl<K, S, C> Histogram<K, S, C>
+where
+    K: Hash + Eq + BinaryFormat,
+    S: BuildHasher,
+    C: Count + BinaryFormat,
+{
+    /// Write the Histogram to `w` in a compact binary format
+    ///
+    /// This is much smaller and faster than going through a text format with the `serde`
+    /// feature. Wrap `w` in a [`BufWriter`](std::io::BufWriter) if it is unbuffered, like a
+    /// [`File`](std::fs::File). The format is described in [`BinaryFormat`].
+    ///
+    /// # Errors
+    /// If writing to `w` fails.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<String>::from_iter(["a", "b", "a"]);
+    ///
+    /// let mut buf = Vec::new();
+    /// hist.write_to(&mut buf).unwrap();
+    ///
+    /// let loaded = Histogram::<String>::read_from(&buf[..]).unwrap();
+    /// assert_eq!(loaded.count("a"), 2);
+    /// assert_eq!(loaded.count("b"), 1);
+    /// ```
+    pub fn write_to<W: Write>(&self, mut w: W) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&[VERSION])?;
+        self.num_categories().write_binary(&mut w)?;
+        for (key, cnt) in self {
+            key.write_binary(&mut w)?;
+            cnt.write_binary(&mut w)?;
+        }
+        w.flush()
+    }
+}
+
+impl<K, S, C> Histogram<K, S, C>
+where
+    K: Hash + Eq + BinaryFormat,
+    S: BuildHasher + Default,
+    C: Count + BinaryFormat,
+{
+    /// Read a Histogram written by [`Histogram::write_to()`] from `r`
+    ///
+    /// Wrap `r` in a [`BufReader`](std::io::BufReader) if it is unbuffered, like a
+    /// [`File`](std::fs::File).
+    ///
+    /// # Errors
+    /// If reading from `r` fails, or the data is not a Histogram in the binary format with the
+    /// same `key` and count types. The error kind is [`io::ErrorKind::InvalidData`] for malformed
+    /// data, including `key`s that occur more than once and counts that are not positive.
+    pub fn read_from<R: Read>(mut r: R) -> io::Result<Self> {
+        let mut header = [0; 5];
+        r.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid_data(
+                "not a histogram, the magic bytes do not match",
+            ));
+        }
+        if header[4] != VERSION {
+            return Err(invalid_data(format!(
+                "unsupported format version {}",
+                header[4]
+            )));
+        }
+
+        let len = usize::read_binary(&mut r)?;
+        let mut hist = Self::default();
+        // Do not trust `len` too much for the allocation, the data could be truncated
+        hist.reserve(len.min(1 << 16));
+        for _ in 0..len {
+            let key = K::read_binary(&mut r)?;
+            let cnt = C::read_binary(&mut r)?;
+            // `write_to()` never repeats a key, and summing repeated counts could overflow
+            if hist.contains_key(&key) {
+                return Err(invalid_data("a key occurs more than once"));
+            }
+            // `write_to()` never writes a key that did not occur, so do not silently drop it
+            if cnt.partial_cmp(&C::ZERO) != Some(Ordering::Greater) {
+                return Err(invalid_data(format!("invalid count {cnt:?}")));
+            }
+            hist.add_owned_n(key, cnt);
+        }
+        Ok(hist)
+    }
+}
+
+fn write_varint<W: Write + ?Sized>(w: &mut W, mut value: u128) -> io::Result<()> {
+    let mut buf = [0; 19];
+    let mut len = 0;
+    loop {
+        // Only the low 7 bits are kept, so this can not truncate
+        #[allow(clippy::cast_possible_truncation)]
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    w.write_all(&buf[..len])
+}
+
+fn read_varint<R: Read + ?Sized>(r: &mut R) -> io::Result<u128> {
+    let mut value = 0;
+    for shift in (0..128).step_by(7) {
+        let mut byte = [0];
+        r.read_exact(&mut byte)?;
+        // The last byte only has room for the top two bits of a `u128`
+        if shift == 126 && byte[0] & 0x7F > 0x03 {
+            return Err(invalid_data("variable length integer overflows 128 bits"));
+        }
+        value |= u128::from(byte[0] & 0x7F) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid_data("variable length integer is too long"))
+}
+
+fn invalid_data<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
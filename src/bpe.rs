@@ -0,0 +1,184 @@
+//! Subword tokenization with byte pair encoding, trained on a [`Histogram`] of words.
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+use hashbrown::HashMap;
+
+use crate::{Count, Histogram, Tokenizer};
+
+/// A subword tokenizer using byte pair encoding (BPE)
+///
+/// Training starts with every word split into its characters and repeatedly merges the most
+/// common pair of adjacent symbols into a new one. Frequent words thereby become single tokens,
+/// while rare words are split into frequent parts like stems and suffixes. This keeps the
+/// vocabulary small for morphologically rich languages, where many word forms are rare.
+///
+/// Despite the name, this works on [`char`]s instead of bytes, so every token is valid UTF-8.
+/// Words are separated by whitespace, tokens never span multiple words.
+///
+/// # Example
+/// ```rust
+/// use histongram::{BytePairEncoding, Histogram};
+///
+/// let words = Histogram::<_>::from_counts([
+///     ("low", 5),
+///     ("lower", 2),
+///     ("newest", 6),
+///     ("widest", 3),
+/// ]);
+/// let bpe = BytePairEncoding::train(&words, 4);
+///
+/// assert_eq!(bpe.merges()[0], ("e".to_string(), "s".to_string()));
+/// assert_eq!(bpe.split_word("lowest"), ["low", "est"]);
+///
+/// // Count the subwords of some text
+/// let mut subwords = Histogram::<String>::new();
+/// subwords.count_text("slowest lower", &bpe);
+/// assert_eq!(subwords.count("low"), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BytePairEncoding {
+    /// The merged pairs, in the order they were learned
+    merges: Vec<(String, String)>,
+    /// The index into `merges` of every pair, as `left -> right -> rank`
+    ranks: HashMap<String, HashMap<String, usize>>,
+}
+
+impl BytePairEncoding {
+    /// Learn up to `num_merges` merges from the counts of `words`
+    ///
+    /// Every `key` is one word, weighted by its count. Training stops early once no adjacent
+    /// symbols are left to merge. Pairs with the same count are merged in lexicographic order, so
+    /// training is deterministic.
+    #[must_use]
+    pub fn train<K, S, C>(words: &Histogram<K, S, C>, num_merges: usize) -> Self
+    where
+        K: Hash + Eq + Borrow<str>,
+        S: BuildHasher,
+        C: Count,
+    {
+        let mut corpus: Vec<(Vec<String>, f64)> = words
+            .iter()
+            .map(|(word, cnt)| {
+                let symbols = word.borrow().chars().map(String::from).collect();
+                (symbols, cnt.to_f64())
+            })
+            .collect();
+
+        let mut merges = Vec::with_capacity(num_merges);
+        for _ in 0..num_merges {
+            let Some((left, right)) = most_common_pair(&corpus) else {
+                break;
+            };
+            for (symbols, _cnt) in &mut corpus {
+                merge_pair(symbols, &left, &right);
+            }
+            merges.push((left, right));
+        }
+
+        Self::from_merges(merges)
+    }
+
+    /// Create a tokenizer from previously learned merges, e.g. stored from
+    /// [`BytePairEncoding::merges()`]
+    ///
+    /// If a pair occurs multiple times, only its first occurrence counts.
+    #[must_use]
+    pub fn from_merges(merges: Vec<(String, String)>) -> Self {
+        let mut ranks: HashMap<String, HashMap<String, usize>> = HashMap::default();
+        for (rank, (left, right)) in merges.iter().enumerate() {
+            ranks
+                .entry(left.clone())
+                .or_default()
+                .entry(right.clone())
+                .or_insert(rank);
+        }
+        Self { merges, ranks }
+    }
+
+    /// The learned merges of two adjacent symbols, in the order they are applied
+    #[must_use]
+    pub fn merges(&self) -> &[(String, String)] {
+        &self.merges
+    }
+
+    /// Split a single `word` into its subword tokens
+    ///
+    /// The learned merges are applied in the order they were learned, so the result is the same
+    /// as during training. Characters that were never merged stay single tokens.
+    #[must_use]
+    pub fn split_word<'a>(&self, word: &'a str) -> Vec<&'a str> {
+        // Byte ranges of the symbols in `word`
+        let mut parts: Vec<(usize, usize)> = word
+            .char_indices()
+            .map(|(idx, c)| (idx, idx + c.len_utf8()))
+            .collect();
+
+        loop {
+            let best = parts
+                .windows(2)
+                .enumerate()
+                .filter_map(|(idx, pair)| {
+                    let [(left_start, left_end), (right_start, right_end)] = [pair[0], pair[1]];
+                    let rank =
+                        self.rank(&word[left_start..left_end], &word[right_start..right_end])?;
+                    Some((rank, idx))
+                })
+                .min();
+            let Some((_rank, idx)) = best else {
+                break;
+            };
+
+            let (_start, end) = parts.remove(idx + 1);
+            parts[idx].1 = end;
+        }
+
+        parts
+            .into_iter()
+            .map(|(start, end)| &word[start..end])
+            .collect()
+    }
+
+    /// The rank of merging `left` and `right`, or `None` if they are never merged
+    fn rank(&self, left: &str, right: &str) -> Option<usize> {
+        self.ranks.get(left)?.get(right).copied()
+    }
+}
+
+impl Tokenizer for BytePairEncoding {
+    fn tokens<'a>(&self, text: &'a str) -> impl Iterator<Item = &'a str> {
+        text.split_whitespace()
+            .flat_map(|word| self.split_word(word))
+    }
+}
+
+/// The most common pair of adjacent symbols, choosing the smallest pair on ties
+fn most_common_pair(corpus: &[(Vec<String>, f64)]) -> Option<(String, String)> {
+    let mut pairs: HashMap<(&str, &str), f64> = HashMap::default();
+    for (symbols, cnt) in corpus {
+        for pair in symbols.windows(2) {
+            *pairs.entry((&pair[0], &pair[1])).or_insert(0.0) += cnt;
+        }
+    }
+
+    pairs
+        .into_iter()
+        .max_by(|(pair_a, a), (pair_b, b)| a.total_cmp(b).then_with(|| pair_b.cmp(pair_a)))
+        .map(|((left, right), _cnt)| (left.to_owned(), right.to_owned()))
+}
+
+/// Merge every occurrence of `left` followed by `right` in `symbols` into a single symbol
+fn merge_pair(symbols: &mut Vec<String>, left: &str, right: &str) {
+    let mut idx = 0;
+    while idx + 1 < symbols.len() {
+        if symbols[idx] == left && symbols[idx + 1] == right {
+            let merged = symbols.remove(idx + 1);
+            symbols[idx].push_str(&merged);
+        }
+        idx += 1;
+    }
+}
@@ -0,0 +1,234 @@
+//! Counting numeric values in ranges, for coarse distributions of lengths, latencies and the like.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt::{self, Display};
+use core::hash::{BuildHasher, Hash};
+
+use crate::{Count, Histogram, OverflowPolicy};
+
+/// A Histogram of values grouped into buckets between fixed edges
+///
+/// `n` edges make `n + 1` buckets: one for everything below the first edge, one between every
+/// two adjacent edges and one for everything from the last edge on. Every bucket includes its
+/// lower edge, but not its upper one. Values that can not be compared to the edges, like `NaN`,
+/// are ignored.
+///
+/// # Example
+/// ```rust
+/// use histongram::BucketedHistogram;
+///
+/// let mut latencies = BucketedHistogram::<u32>::new(vec![10, 100, 1000]);
+/// latencies.extend([3, 42, 57, 250, 4000]);
+///
+/// let buckets: Vec<_> = latencies
+///     .buckets()
+///     .map(|(bucket, cnt)| format!("{bucket}: {cnt}"))
+///     .collect();
+/// assert_eq!(buckets, ["< 10: 1", "[10, 100): 2", "[100, 1000): 1", ">= 1000: 1"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BucketedHistogram<T, C: Count = usize> {
+    edges: Vec<T>,
+    /// One more count than there are edges
+    counts: Vec<C>,
+    overflow: OverflowPolicy,
+}
+
+/// Compares the edges and counts, the [`OverflowPolicy`] does not matter
+impl<T: PartialEq, C: Count> PartialEq for BucketedHistogram<T, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.edges == other.edges && self.counts == other.counts
+    }
+}
+
+/// The range of values of a bucket in a [`BucketedHistogram`]
+///
+/// The range includes `start`, but not `end`. A missing bound means the range is unbounded in
+/// that direction. [`Display`] labels the bucket like `[10, 100)`, `< 10` or `>= 100`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bucket<T> {
+    /// Lower bound, included in the bucket
+    pub start: Option<T>,
+    /// Upper bound, not included in the bucket
+    pub end: Option<T>,
+}
+
+impl<T: PartialOrd> Bucket<T> {
+    /// Check if `value` falls into this bucket
+    #[must_use]
+    pub fn contains(&self, value: &T) -> bool {
+        self.start.as_ref().is_none_or(|start| start <= value)
+            && self.end.as_ref().is_none_or(|end| value < end)
+    }
+}
+
+impl<T: Display> Display for Bucket<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.start, &self.end) {
+            (Some(start), Some(end)) => write!(f, "[{start}, {end})"),
+            (Some(start), None) => write!(f, ">= {start}"),
+            (None, Some(end)) => write!(f, "< {end}"),
+            (None, None) => f.write_str("all"),
+        }
+    }
+}
+
+impl<T: PartialOrd + Copy, C: Count> BucketedHistogram<T, C> {
+    /// Create a new empty `BucketedHistogram` with the given bucket `edges`
+    ///
+    /// # Panics
+    /// If `edges` are not strictly increasing, e.g. if they contain duplicates or `NaN`.
+    #[must_use]
+    pub fn new(edges: Vec<T>) -> Self {
+        assert!(
+            edges.windows(2).all(|pair| pair[0] < pair[1]),
+            "bucket edges must be strictly increasing"
+        );
+        assert!(
+            edges.iter().all(|edge| edge.partial_cmp(edge).is_some()),
+            "bucket edges must be comparable"
+        );
+
+        Self {
+            counts: vec![C::ZERO; edges.len() + 1],
+            edges,
+            overflow: OverflowPolicy::Panic,
+        }
+    }
+
+    /// The edges between the buckets
+    #[must_use]
+    pub fn edges(&self) -> &[T] {
+        &self.edges
+    }
+
+    /// The [`OverflowPolicy`] used when counts get too large for `C`
+    #[must_use]
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow
+    }
+
+    /// Set the [`OverflowPolicy`] used when counts get too large for `C`
+    ///
+    /// The default is [`OverflowPolicy::Panic`].
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.overflow = policy;
+    }
+
+    /// Add a single occurrence of `value` to its bucket
+    ///
+    /// # Panics
+    /// If the count of the bucket overflows and the [`OverflowPolicy`] is
+    /// [`OverflowPolicy::Panic`].
+    pub fn add(&mut self, value: T) {
+        self.add_n(value, C::ONE);
+    }
+
+    /// Add `n` occurrences of `value` to its bucket
+    ///
+    /// Like [`Histogram::add_owned_n()`], adding zero, negative or NaN occurrences does nothing.
+    ///
+    /// # Panics
+    /// If the count of the bucket overflows and the [`OverflowPolicy`] is
+    /// [`OverflowPolicy::Panic`].
+    pub fn add_n(&mut self, value: T, n: C) {
+        // Buckets only ever grow, this also skips NaN
+        if n.partial_cmp(&C::ZERO) != Some(Ordering::Greater) {
+            return;
+        }
+        if let Some(idx) = self.bucket_index(&value) {
+            self.overflow.add(&mut self.counts[idx], n);
+        }
+    }
+
+    /// Count of the bucket `value` falls into
+    #[must_use]
+    pub fn count(&self, value: &T) -> C {
+        self.bucket_index(value)
+            .map_or(C::ZERO, |idx| self.counts[idx])
+    }
+
+    /// The bucket `value` falls into, or `None` if it can not be compared to the edges
+    #[must_use]
+    pub fn bucket(&self, value: &T) -> Option<Bucket<T>> {
+        self.bucket_index(value).map(|idx| self.bucket_at(idx))
+    }
+
+    /// Total number of values added to any bucket
+    ///
+    /// # Panics
+    /// If the total overflows `C` and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    #[must_use]
+    pub fn num_instances(&self) -> C {
+        self.overflow.sum(self.counts.iter().copied())
+    }
+
+    /// Iterate over all buckets in ascending order together with their counts
+    ///
+    /// This includes empty buckets.
+    pub fn buckets(&self) -> impl Iterator<Item = (Bucket<T>, C)> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .map(|(idx, &cnt)| (self.bucket_at(idx), cnt))
+    }
+
+    fn bucket_at(&self, idx: usize) -> Bucket<T> {
+        Bucket {
+            start: idx.checked_sub(1).map(|prev| self.edges[prev]),
+            end: self.edges.get(idx).copied(),
+        }
+    }
+
+    fn bucket_index(&self, value: &T) -> Option<usize> {
+        // Only `NaN` and the like are not equal to themselves
+        value.partial_cmp(value)?;
+        Some(self.edges.partition_point(|edge| edge <= value))
+    }
+}
+
+impl<T: PartialOrd + Copy, C: Count> Extend<T> for BucketedHistogram<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.add(value);
+        }
+    }
+}
+
+impl<K, S, C> Histogram<K, S, C>
+where
+    K: Hash + Eq + PartialOrd + Copy,
+    S: BuildHasher,
+    C: Count,
+{
+    /// Group the numeric `key`s into buckets between `edges`, see [`BucketedHistogram`]
+    ///
+    /// The buckets use the same [`OverflowPolicy`] as `self`.
+    ///
+    /// # Panics
+    /// If `edges` are not strictly increasing, or a bucket count overflows and the
+    /// [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let text = "a quick brown fox jumps over the extraordinarily lazy dog";
+    /// let lengths = Histogram::<_>::from_owned_iter(text.split(' ').map(str::len));
+    ///
+    /// let buckets = lengths.bucketize(vec![4, 8]);
+    /// assert_eq!(buckets.count(&1), 4);
+    /// assert_eq!(buckets.count(&5), 5);
+    /// assert_eq!(buckets.count(&100), 1);
+    /// ```
+    #[must_use]
+    pub fn bucketize(&self, edges: Vec<K>) -> BucketedHistogram<K, C> {
+        let mut buckets = BucketedHistogram::new(edges);
+        buckets.set_overflow_policy(self.overflow);
+        for (&key, cnt) in self {
+            buckets.add_n(key, cnt);
+        }
+        buckets
+    }
+}
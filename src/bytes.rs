@@ -0,0 +1,43 @@
+//! Counting n-grams of raw bytes.
+
+use core::hash::BuildHasher;
+
+use crate::{Count, Histogram};
+
+impl<const N: usize, S, C> Histogram<[u8; N], S, C>
+where
+    S: BuildHasher,
+    C: Count,
+{
+    /// Count every overlapping run of `N` consecutive bytes in `bytes`
+    ///
+    /// Nothing is decoded, so this works on binary data and text of unknown encoding alike. This
+    /// is useful for fingerprinting file formats and protocols, or to detect the charset of text
+    /// before decoding it. Inputs shorter than `N` bytes are not counted at all.
+    ///
+    /// # Panics
+    /// If `N` is zero, or the count of an n-gram overflows and the
+    /// [`OverflowPolicy`](crate::OverflowPolicy) is
+    /// [`OverflowPolicy::Panic`](crate::OverflowPolicy::Panic).
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let mut hist = Histogram::<[u8; 2]>::new();
+    ///
+    /// hist.count_byte_ngrams(b"abab\xff");
+    /// assert_eq!(hist.count(b"ab"), 2);
+    /// assert_eq!(hist.count(b"ba"), 1);
+    /// assert_eq!(hist.count(&[b'b', 0xff]), 1);
+    /// assert_eq!(hist.num_instances(), 4);
+    /// ```
+    pub fn count_byte_ngrams(&mut self, bytes: &[u8]) {
+        assert!(N > 0, "n-grams must be at least one byte long");
+
+        for window in bytes.windows(N) {
+            let mut ngram = [0; N];
+            ngram.copy_from_slice(window);
+            self.add_owned(ngram);
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! Estimating the number of distinct `key`s with the `HyperLogLog` algorithm.
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+use crate::DefaultHashBuilder;
+
+/// A `HyperLogLog` estimator of the number of distinct `key`s in a stream
+///
+/// This answers the same question as [`Histogram::num_categories()`](crate::Histogram::num_categories)
+/// without storing the `key`s. It uses `2^precision` bytes of memory and has a relative standard
+/// error of about `1.04 / sqrt(2^precision)`, i.e. about 1.6% for the default precision of 12.
+///
+/// # Example
+/// ```rust
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::BuildHasherDefault;
+///
+/// use histongram::{CardinalityEstimator, Histogram};
+///
+/// let text = "the quick brown fox jumps over the lazy dog";
+///
+/// // A fixed hasher makes the estimate reproducible, the default one is randomly seeded
+/// let hasher = BuildHasherDefault::<DefaultHasher>::default();
+/// let mut estimator = CardinalityEstimator::<str, _>::with_precision_and_hasher(12, hasher);
+/// for word in text.split_whitespace() {
+///     estimator.add_ref(word);
+/// }
+/// let exact: Histogram<&str> = text.split_whitespace().collect();
+///
+/// assert_eq!(estimator.estimate().round(), exact.num_categories() as f64);
+/// ```
+#[derive(Debug)]
+pub struct CardinalityEstimator<K: ?Sized + Hash, S: BuildHasher = DefaultHashBuilder> {
+    registers: Box<[u8]>,
+    precision: u8,
+    hash_builder: S,
+    _key: PhantomData<fn(&K)>,
+}
+
+// Not derived, as that would needlessly require `K` to be `Clone`
+impl<K: ?Sized + Hash, S: BuildHasher + Clone> Clone for CardinalityEstimator<K, S> {
+    fn clone(&self) -> Self {
+        Self {
+            registers: self.registers.clone(),
+            precision: self.precision,
+            hash_builder: self.hash_builder.clone(),
+            _key: PhantomData,
+        }
+    }
+}
+
+impl<K: ?Sized + Hash> CardinalityEstimator<K> {
+    /// Create a new empty estimator with the default precision of 12
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_precision(12)
+    }
+
+    /// Create a new empty estimator using `2^precision` registers
+    ///
+    /// # Panics
+    /// If `precision` is not between 4 and 18.
+    #[must_use]
+    pub fn with_precision(precision: u8) -> Self {
+        Self::with_precision_and_hasher(precision, DefaultHashBuilder::default())
+    }
+}
+
+impl<K: ?Sized + Hash> Default for CardinalityEstimator<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: ?Sized + Hash, S: BuildHasher> CardinalityEstimator<K, S> {
+    /// Create a new empty estimator using `2^precision` registers and the given `hash_builder`
+    ///
+    /// To [`CardinalityEstimator::merge()`] estimators they have to use the same precision and
+    /// hash the same way.
+    ///
+    /// # Panics
+    /// If `precision` is not between 4 and 18.
+    #[must_use]
+    pub fn with_precision_and_hasher(precision: u8, hash_builder: S) -> Self {
+        assert!(
+            (4..=18).contains(&precision),
+            "precision must be between 4 and 18, got {precision}"
+        );
+
+        Self {
+            registers: vec![0; 1 << precision].into_boxed_slice(),
+            precision,
+            hash_builder,
+            _key: PhantomData,
+        }
+    }
+
+    /// The precision, i.e. the base 2 logarithm of the number of registers
+    #[must_use]
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Relative standard error of [`CardinalityEstimator::estimate()`]
+    #[must_use]
+    pub fn standard_error(&self) -> f64 {
+        1.04 / self.num_registers().sqrt()
+    }
+
+    /// Check if nothing was added so far
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.registers.iter().all(|&reg| reg == 0)
+    }
+
+    /// Add a single occurrence of `val`
+    pub fn add_ref<Q>(&mut self, val: &Q)
+    where
+        Q: ?Sized + Hash,
+        K: Borrow<Q>,
+    {
+        let hash = self.hash_builder.hash_one(val);
+        let precision = u32::from(self.precision);
+
+        // The first `precision` bits choose the register, the position of the first set bit in the
+        // remaining ones is what the register remembers the maximum of.
+        // At most 18 bits are left after the shift, which fit into any `usize`.
+        #[allow(clippy::cast_possible_truncation)]
+        let idx = (hash >> (64 - precision)) as usize;
+        // `leading_zeros` is at most 64, so this always fits into a `u8`
+        #[allow(clippy::cast_possible_truncation)]
+        let rank = ((hash << precision).leading_zeros().min(64 - precision) + 1) as u8;
+
+        let register = &mut self.registers[idx];
+        *register = (*register).max(rank);
+    }
+
+    /// Add a single occurrence of `val`
+    ///
+    /// As the `key`s are not stored, this is the same as [`CardinalityEstimator::add_ref()`].
+    pub fn add_owned(&mut self, val: K)
+    where
+        K: Sized,
+    {
+        self.add_ref(&val);
+    }
+
+    /// Estimated number of distinct `key`s added so far
+    #[must_use]
+    pub fn estimate(&self) -> f64 {
+        let m = self.num_registers();
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|&reg| 2f64.powi(-i32::from(reg)))
+            .sum();
+        let raw = alpha * m * m / sum;
+
+        let zeros: u32 = self.registers.iter().map(|&reg| u32::from(reg == 0)).sum();
+        if raw <= 2.5 * m && zeros > 0 {
+            // Linear counting is more accurate for small cardinalities
+            m * (m / f64::from(zeros)).ln()
+        } else {
+            raw
+        }
+    }
+
+    /// Combine the `key`s seen by `other` into `self`, as if they were all added to `self`
+    ///
+    /// This only gives meaningful results if both hash `key`s the same way. Estimators created
+    /// with [`CardinalityEstimator::new()`] may use differently seeded hashers, so create the
+    /// others by cloning an empty estimator, or by passing clones of one `hash_builder` to
+    /// [`CardinalityEstimator::with_precision_and_hasher()`].
+    ///
+    /// # Panics
+    /// If `other` has a different precision.
+    pub fn merge(&mut self, other: &Self) {
+        assert_eq!(
+            self.precision, other.precision,
+            "can only merge estimators with the same precision"
+        );
+
+        for (reg, &other) in self.registers.iter_mut().zip(&*other.registers) {
+            *reg = (*reg).max(other);
+        }
+    }
+
+    /// Forget all `key`s added so far
+    pub fn clear(&mut self) {
+        self.registers.fill(0);
+    }
+
+    fn num_registers(&self) -> f64 {
+        f64::from(1_u32 << self.precision)
+    }
+}
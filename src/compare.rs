@@ -0,0 +1,334 @@
+//! Comparing two [`Histogram`]s by treating them as empirical probability distributions.
+
+use std::hash::{BuildHasher, Hash};
+
+use crate::{Count, Histogram};
+
+impl<K: Hash + Eq, S: BuildHasher, C: Count> Histogram<K, S, C> {
+    /// Kullback–Leibler divergence `D(self || other)` in bits
+    ///
+    /// Both histograms are treated as empirical distributions over their `key`s. The divergence is
+    /// `0.0` for identical distributions and grows the more `other` differs from `self`.
+    ///
+    /// If `self` contains a `key` that never occurred in `other` the divergence is infinite. Use
+    /// [`Histogram::kl_divergence_smoothed()`] if that is not what you want. Categories only
+    /// present in `other` do not contribute. An empty `self` has a divergence of `0.0`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let p = Histogram::<_>::from_owned_iter("aabb".chars());
+    /// let q = Histogram::<_>::from_owned_iter("abbb".chars());
+    ///
+    /// assert_eq!(p.kl_divergence(&p), 0.0);
+    /// assert!(p.kl_divergence(&q) > 0.0);
+    ///
+    /// let r = Histogram::<_>::from_owned_iter("bbbb".chars());
+    /// assert_eq!(p.kl_divergence(&r), f64::INFINITY);
+    /// ```
+    #[must_use]
+    pub fn kl_divergence<S2: BuildHasher>(&self, other: &Histogram<K, S2, C>) -> f64 {
+        self.kl_divergence_smoothed(other, 0.0)
+    }
+
+    /// Kullback–Leibler divergence `D(self || other)` in bits with additive smoothing
+    ///
+    /// Before comparing, `alpha` is added to the count of every `key` that occurred in either
+    /// histogram (Laplace smoothing for `alpha == 1.0`). So for any `alpha > 0.0` the divergence
+    /// is finite, even if the sets of `key`s differ. With `alpha == 0.0` this is the same as
+    /// [`Histogram::kl_divergence()`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let p = Histogram::<_>::from_owned_iter("aabb".chars());
+    /// let q = Histogram::<_>::from_owned_iter("bbbb".chars());
+    ///
+    /// assert!(p.kl_divergence_smoothed(&q, 1.0).is_finite());
+    /// ```
+    #[must_use]
+    pub fn kl_divergence_smoothed<S2: BuildHasher>(
+        &self,
+        other: &Histogram<K, S2, C>,
+        alpha: f64,
+    ) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+
+        let (total_p, total_q) = smoothed_totals(self, other, alpha);
+        union_counts(self, other)
+            .map(|(_key, p, q)| (p.to_f64() + alpha, q.to_f64() + alpha))
+            .filter(|&(p, _q)| p > 0.0)
+            .map(|(p, q)| {
+                let p = p / total_p;
+                // Avoid `0 / 0` if `other` is empty, so this correctly becomes infinite
+                let q = if q == 0.0 { 0.0 } else { q / total_q };
+                p * (p / q).log2()
+            })
+            .sum()
+    }
+
+    /// Jensen–Shannon divergence between `self` and `other` in bits
+    ///
+    /// In contrast to [`Histogram::kl_divergence()`] this is symmetric and always finite, even if
+    /// the `key`s of both histograms differ. It is `0.0` for identical distributions and `1.0` for
+    /// distributions without any common `key`. If only one of the histograms is empty, they are
+    /// also treated as having nothing in common.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let p = Histogram::<_>::from_owned_iter("aabb".chars());
+    /// let q = Histogram::<_>::from_owned_iter("abbb".chars());
+    /// let r = Histogram::<_>::from_owned_iter("cccc".chars());
+    ///
+    /// assert_eq!(p.jensen_shannon(&p), 0.0);
+    /// assert_eq!(p.jensen_shannon(&q), q.jensen_shannon(&p));
+    /// assert_eq!(p.jensen_shannon(&r), 1.0);
+    /// ```
+    #[must_use]
+    pub fn jensen_shannon<S2: BuildHasher>(&self, other: &Histogram<K, S2, C>) -> f64 {
+        match (self.is_empty(), other.is_empty()) {
+            (true, true) => return 0.0,
+            (true, false) | (false, true) => return 1.0,
+            (false, false) => {}
+        }
+
+        let total_p = self.instances_f64();
+        let total_q = other.instances_f64();
+
+        // Half of `p * log2(p / m)`, which is defined as 0 for `p == 0`
+        let half_term = |p: f64, m: f64| {
+            if p > 0.0 {
+                0.5 * p * (p / m).log2()
+            } else {
+                0.0
+            }
+        };
+
+        union_counts(self, other)
+            .map(|(_key, p, q)| {
+                let (p, q) = (p.to_f64() / total_p, q.to_f64() / total_q);
+                let m = p.midpoint(q);
+                half_term(p, m) + half_term(q, m)
+            })
+            .sum::<f64>()
+            // Rounding errors could make this slightly leave the valid range
+            .clamp(0.0, 1.0)
+    }
+
+    /// Jensen–Shannon distance between `self` and `other`
+    ///
+    /// This is the square root of [`Histogram::jensen_shannon()`], which in contrast to the
+    /// divergence is a proper metric. It lies between `0.0` and `1.0`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let p = Histogram::<_>::from_owned_iter("aabb".chars());
+    /// let q = Histogram::<_>::from_owned_iter("abbb".chars());
+    ///
+    /// assert_eq!(p.jensen_shannon_distance(&q), p.jensen_shannon(&q).sqrt());
+    /// ```
+    #[must_use]
+    pub fn jensen_shannon_distance<S2: BuildHasher>(&self, other: &Histogram<K, S2, C>) -> f64 {
+        self.jensen_shannon(other).sqrt()
+    }
+
+    /// Cosine similarity of the counts in `self` and `other`
+    ///
+    /// The counts are treated as sparse vectors with one dimension per `key`. The similarity is
+    /// `1.0` for histograms with proportional counts and `0.0` if they do not share any `key`. If
+    /// either histogram is empty the similarity is also `0.0`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let p = Histogram::<_>::from_owned_iter("ab".chars());
+    /// let q = Histogram::<_>::from_owned_iter("aabb".chars());
+    /// let r = Histogram::<_>::from_owned_iter("cd".chars());
+    ///
+    /// assert!((p.cosine_similarity(&q) - 1.0).abs() < 1e-12);
+    /// assert_eq!(p.cosine_similarity(&r), 0.0);
+    /// ```
+    #[must_use]
+    pub fn cosine_similarity<S2: BuildHasher>(&self, other: &Histogram<K, S2, C>) -> f64 {
+        // Only `key`s present in both contribute to the dot product
+        let dot: f64 = if self.num_categories() <= other.num_categories() {
+            self.iter()
+                .map(|(key, cnt)| cnt.to_f64() * other.count(key).to_f64())
+                .sum()
+        } else {
+            other
+                .iter()
+                .map(|(key, cnt)| cnt.to_f64() * self.count(key).to_f64())
+                .sum()
+        };
+        if dot == 0.0 {
+            return 0.0;
+        }
+
+        dot / (euclidean_norm(self) * euclidean_norm(other))
+    }
+
+    /// Total variation distance between `self` and `other`
+    ///
+    /// This is half the sum of the absolute differences of the relative frequencies, i.e. the
+    /// largest difference in probability the two distributions can assign to the same set of
+    /// `key`s. It is `0.0` for identical distributions and `1.0` for distributions without any
+    /// common `key`. If only one of the histograms is empty, they are also treated as having
+    /// nothing in common.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let p = Histogram::<_>::from_owned_iter("aabb".chars());
+    /// let q = Histogram::<_>::from_owned_iter("abbb".chars());
+    /// let r = Histogram::<_>::from_owned_iter("cccc".chars());
+    ///
+    /// assert_eq!(p.total_variation(&q), 0.25);
+    /// assert_eq!(p.total_variation(&r), 1.0);
+    /// ```
+    #[must_use]
+    pub fn total_variation<S2: BuildHasher>(&self, other: &Histogram<K, S2, C>) -> f64 {
+        match (self.is_empty(), other.is_empty()) {
+            (true, true) => return 0.0,
+            (true, false) | (false, true) => return 1.0,
+            (false, false) => {}
+        }
+
+        let total_p = self.instances_f64();
+        let total_q = other.instances_f64();
+        let sum: f64 = union_counts(self, other)
+            .map(|(_key, p, q)| (p.to_f64() / total_p - q.to_f64() / total_q).abs())
+            .sum();
+        // Rounding errors could make this slightly leave the valid range
+        (sum / 2.0).clamp(0.0, 1.0)
+    }
+
+    /// Hellinger distance between `self` and `other`
+    ///
+    /// This is `sqrt(1 - BC)` where `BC` is the Bhattacharyya coefficient, the sum of
+    /// `sqrt(p * q)` over all `key`s. It is `0.0` for identical distributions and `1.0` for
+    /// distributions without any common `key`. If only one of the histograms is empty, they are
+    /// also treated as having nothing in common.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let p = Histogram::<_>::from_owned_iter("aabb".chars());
+    /// let q = Histogram::<_>::from_owned_iter("abbb".chars());
+    /// let r = Histogram::<_>::from_owned_iter("cccc".chars());
+    ///
+    /// assert_eq!(p.hellinger(&p), 0.0);
+    /// assert_eq!(p.hellinger(&q), q.hellinger(&p));
+    /// assert_eq!(p.hellinger(&r), 1.0);
+    /// ```
+    #[must_use]
+    pub fn hellinger<S2: BuildHasher>(&self, other: &Histogram<K, S2, C>) -> f64 {
+        match (self.is_empty(), other.is_empty()) {
+            (true, true) => return 0.0,
+            (true, false) | (false, true) => return 1.0,
+            (false, false) => {}
+        }
+
+        let total_p = self.instances_f64();
+        let total_q = other.instances_f64();
+        // Only `key`s present in both contribute to the coefficient
+        let coefficient: f64 = self
+            .iter()
+            .map(|(key, p)| (p.to_f64() / total_p * other.count(key).to_f64() / total_q).sqrt())
+            .sum();
+        // Rounding errors could make this slightly leave the valid range
+        (1.0 - coefficient).clamp(0.0, 1.0).sqrt()
+    }
+
+    /// Check if `self` and `other` have the same relative frequencies up to `epsilon`
+    ///
+    /// In contrast to `==` this ignores the total number of instances and tolerates small
+    /// rounding differences, e.g. of float counts computed on different platforms. A `key` absent
+    /// on one side has a relative frequency of `0.0` there. Two empty histograms are equal, but an
+    /// empty one never equals a non-empty one.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let p = Histogram::<_>::from_owned_iter("aab".chars());
+    /// let q = Histogram::<_>::from_owned_iter("aaaabb".chars());
+    /// let r = Histogram::<_>::from_owned_iter("aaabb".chars());
+    ///
+    /// assert_ne!(p, q);
+    /// assert!(p.approx_eq(&q, 1e-12));
+    /// assert!(!p.approx_eq(&r, 0.05));
+    /// assert!(p.approx_eq(&r, 0.1));
+    /// ```
+    #[must_use]
+    pub fn approx_eq<S2: BuildHasher>(&self, other: &Histogram<K, S2, C>, epsilon: f64) -> bool {
+        match (self.is_empty(), other.is_empty()) {
+            (true, true) => return true,
+            (true, false) | (false, true) => return false,
+            (false, false) => {}
+        }
+
+        let total_p = self.instances_f64();
+        let total_q = other.instances_f64();
+        union_counts(self, other)
+            .all(|(_key, p, q)| (p.to_f64() / total_p - q.to_f64() / total_q).abs() <= epsilon)
+    }
+}
+
+/// Iterate over the union of `key`s of `a` and `b` together with their counts on both sides
+///
+/// Every `key` is yielded exactly once, with a count of zero on the side it is absent from.
+pub(crate) fn union_counts<'a, K, S1, S2, C>(
+    a: &'a Histogram<K, S1, C>,
+    b: &'a Histogram<K, S2, C>,
+) -> impl Iterator<Item = (&'a K, C, C)>
+where
+    K: Hash + Eq,
+    S1: BuildHasher,
+    S2: BuildHasher,
+    C: Count,
+{
+    let in_a = a.iter().map(|(key, cnt)| (key, cnt, b.count(key)));
+    let only_b = b
+        .iter()
+        .filter(|(key, _cnt)| !a.contains_key(*key))
+        .map(|(key, cnt)| (key, C::ZERO, cnt));
+    in_a.chain(only_b)
+}
+
+/// Length of the counts of `hist` seen as a vector
+fn euclidean_norm<K: Hash + Eq, S: BuildHasher, C: Count>(hist: &Histogram<K, S, C>) -> f64 {
+    hist.iter()
+        .map(|(_key, cnt)| cnt.to_f64().powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Totals of both histograms after adding `alpha` to every `key` of their union
+fn smoothed_totals<K, S1, S2, C>(
+    a: &Histogram<K, S1, C>,
+    b: &Histogram<K, S2, C>,
+    alpha: f64,
+) -> (f64, f64)
+where
+    K: Hash + Eq,
+    S1: BuildHasher,
+    S2: BuildHasher,
+    C: Count,
+{
+    let extra = if alpha == 0.0 {
+        0.0
+    } else {
+        alpha * to_f64(union_counts(a, b).count())
+    };
+    (a.instances_f64() + extra, b.instances_f64() + extra)
+}
+
+// Rounding is fine when the numbers get to large to fit f64
+#[allow(clippy::cast_precision_loss)]
+fn to_f64(n: usize) -> f64 {
+    n as f64
+}
@@ -0,0 +1,232 @@
+//! A [`Histogram`] that multiple threads can count into at the same time.
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+use std::num::NonZeroUsize;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use hashbrown::HashMap;
+
+use crate::{Count, DefaultHashBuilder, Histogram};
+
+/// A Histogram split into shards that are each behind their own [`Mutex`]
+///
+/// All methods take `&self`, so the Histogram can be shared between threads, for example with an
+/// [`Arc`](std::sync::Arc) or scoped threads. Every `key` always ends up in the same shard, so
+/// threads only block each other if they happen to count `key`s of the same shard at the same
+/// time. Use [`ConcurrentHistogram::snapshot()`] or [`ConcurrentHistogram::into_histogram()`]
+/// to analyze the counts.
+///
+/// # Example
+/// ```rust
+/// use histongram::ConcurrentHistogram;
+///
+/// let hist = ConcurrentHistogram::<String>::new();
+///
+/// std::thread::scope(|s| {
+///     for line in ["a b c", "a b", "a"] {
+///         let hist = &hist;
+///         s.spawn(move || {
+///             for word in line.split_whitespace() {
+///                 hist.add_ref(word);
+///             }
+///         });
+///     }
+/// });
+///
+/// let hist = hist.into_histogram();
+/// assert_eq!(hist.count("a"), 3);
+/// assert_eq!(hist.count("b"), 2);
+/// assert_eq!(hist.count("c"), 1);
+/// ```
+#[derive(Debug)]
+pub struct ConcurrentHistogram<K: Hash + Eq, S: BuildHasher = DefaultHashBuilder, C: Count = usize>
+{
+    shards: Box<[Mutex<Histogram<K, S, C>>]>,
+    hash_builder: S,
+}
+
+impl<K: Hash + Eq> ConcurrentHistogram<K> {
+    /// Create a new empty `ConcurrentHistogram`
+    ///
+    /// The number of shards is a small multiple of the available parallelism.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new empty `ConcurrentHistogram` with `shards` shards
+    ///
+    /// More shards mean less contention between threads, but more memory overhead.
+    ///
+    /// # Panics
+    /// If `shards` is `0`.
+    #[must_use]
+    pub fn with_shards(shards: usize) -> Self {
+        Self::with_shards_and_hasher(shards, DefaultHashBuilder::default())
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher + Clone> ConcurrentHistogram<K, S> {
+    /// Create a new empty `ConcurrentHistogram` with `shards` shards using the given
+    /// `hash_builder`
+    ///
+    /// See [`Histogram::with_hasher()`].
+    ///
+    /// # Panics
+    /// If `shards` is `0`.
+    #[must_use]
+    pub fn with_shards_and_hasher(shards: usize, hash_builder: S) -> Self {
+        Self::with_shards_generic(shards, hash_builder)
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher + Clone, C: Count> ConcurrentHistogram<K, S, C> {
+    fn with_shards_generic(shards: usize, hash_builder: S) -> Self {
+        assert!(shards > 0, "a ConcurrentHistogram needs at least one shard");
+
+        let shards = (0..shards)
+            .map(|_| Mutex::new(HashMap::with_hasher(hash_builder.clone()).into()))
+            .collect();
+        Self {
+            shards,
+            hash_builder,
+        }
+    }
+
+    /// Number of shards the `key`s are split into
+    #[must_use]
+    pub fn num_shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Add a single occurrence of `val`, see [`Histogram::add_ref()`]
+    ///
+    /// # Panics
+    /// If the count of `val` overflows, see [`OverflowPolicy`](crate::OverflowPolicy).
+    pub fn add_ref<'a, Q>(&self, val: &'a Q)
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q> + From<&'a Q>,
+    {
+        self.shard_for(val).add_ref(val);
+    }
+
+    /// Add a single occurrence of `val`, see [`Histogram::add_owned()`]
+    ///
+    /// # Panics
+    /// If the count of `val` overflows, see [`OverflowPolicy`](crate::OverflowPolicy).
+    pub fn add_owned(&self, val: K) {
+        self.shard_for(&val).add_owned(val);
+    }
+
+    /// Add `n` occurrences of `val`, see [`Histogram::add_owned_n()`]
+    ///
+    /// # Panics
+    /// If the count of `val` overflows, see [`OverflowPolicy`](crate::OverflowPolicy).
+    pub fn add_owned_n(&self, val: K, n: C) {
+        self.shard_for(&val).add_owned_n(val, n);
+    }
+
+    /// Get the number of times `key` was added so far, see [`Histogram::count()`]
+    pub fn count<Q>(&self, key: &Q) -> C
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        self.shard_for(key).count(key)
+    }
+
+    /// Number of categories in all shards
+    ///
+    /// Other threads may add `key`s while the shards are visited one after another.
+    #[must_use]
+    pub fn num_categories(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| lock(shard).num_categories())
+            .sum()
+    }
+
+    /// Total number of instances in all shards
+    ///
+    /// Other threads may add `key`s while the shards are visited one after another.
+    ///
+    /// # Panics
+    /// If the total overflows `C` and the [`OverflowPolicy`](crate::OverflowPolicy) is
+    /// [`OverflowPolicy::Panic`](crate::OverflowPolicy::Panic).
+    #[must_use]
+    pub fn num_instances(&self) -> C {
+        let mut total = C::ZERO;
+        for shard in &*self.shards {
+            let shard = lock(shard);
+            shard.overflow.add(&mut total, shard.num_instances());
+        }
+        total
+    }
+
+    /// Copy the current counts into a regular [`Histogram`]
+    ///
+    /// The shards are locked one after another, so counts added by other threads in the
+    /// meantime may or may not be included.
+    #[must_use]
+    pub fn snapshot(&self) -> Histogram<K, S, C>
+    where
+        K: Clone,
+    {
+        let mut map = HashMap::with_hasher(self.hash_builder.clone());
+        for shard in &*self.shards {
+            let shard = lock(shard);
+            map.reserve(shard.num_categories());
+            // Every `key` is in exactly one shard, so there is nothing to sum up
+            map.extend(shard.iter().map(|(key, cnt)| (key.clone(), cnt)));
+        }
+        map.into()
+    }
+
+    /// Combine all shards into a regular [`Histogram`] without copying the `key`s
+    #[must_use]
+    pub fn into_histogram(self) -> Histogram<K, S, C> {
+        let mut map = HashMap::with_hasher(self.hash_builder);
+        for shard in self.shards.into_vec() {
+            let shard = shard.into_inner().unwrap_or_else(PoisonError::into_inner);
+            map.reserve(shard.num_categories());
+            map.extend(shard);
+        }
+        map.into()
+    }
+
+    /// Lock the shard that `key` belongs to
+    fn shard_for<Q>(&self, key: &Q) -> MutexGuard<'_, Histogram<K, S, C>>
+    where
+        Q: ?Sized + Hash,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        // The low and the top bits are used by the map inside each shard, so choosing the shard
+        // by them would cluster all `key`s of a shard into the same buckets.
+        // Only 24 bits are left after the shift, which fit into any `usize`.
+        #[allow(clippy::cast_possible_truncation)]
+        let idx = (hash >> 40) as usize % self.shards.len();
+        lock(&self.shards[idx])
+    }
+}
+
+impl<K, S, C> Default for ConcurrentHistogram<K, S, C>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Clone + Default,
+    C: Count,
+{
+    fn default() -> Self {
+        let parallelism = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        Self::with_shards_generic(4 * parallelism, S::default())
+    }
+}
+
+/// Lock a shard, ignoring poisoning
+///
+/// A thread can only panic while holding the lock if a count overflowed, which happens before the
+/// shard is modified. So the shard is still consistent.
+fn lock<T>(shard: &Mutex<T>) -> MutexGuard<'_, T> {
+    shard.lock().unwrap_or_else(PoisonError::into_inner)
+}
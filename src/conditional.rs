@@ -0,0 +1,195 @@
+//! Counting `key`s separately per context, e.g. the words following every word for bigram models.
+
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+use hashbrown::HashMap;
+
+use crate::{Count, DefaultHashBuilder, Histogram};
+
+/// A Histogram of `key`s for every context `X`
+///
+/// This is the building block of conditional probabilities like `P(word | previous word)`.
+/// Contexts only exist once something was counted in them.
+///
+/// # Example
+/// ```rust
+/// use histongram::ConditionalHistogram;
+///
+/// let words: Vec<_> = "the cat sat on the mat".split(' ').collect();
+///
+/// let mut bigrams = ConditionalHistogram::<String, String>::new();
+/// for pair in words.windows(2) {
+///     bigrams.add_ref(pair[0], pair[1]);
+/// }
+///
+/// assert_eq!(bigrams.prob("the", "cat"), 0.5);
+/// assert_eq!(bigrams.prob("cat", "sat"), 1.0);
+/// assert_eq!(bigrams.marginal().count("the"), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConditionalHistogram<X, K, S = DefaultHashBuilder, C = usize>
+where
+    X: Hash + Eq,
+    K: Hash + Eq,
+    S: BuildHasher,
+    C: Count,
+{
+    contexts: HashMap<X, Histogram<K, S, C>, S>,
+}
+
+impl<X: Hash + Eq, K: Hash + Eq> ConditionalHistogram<X, K> {
+    /// Create a new empty `ConditionalHistogram`
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<X, K, S, C> Default for ConditionalHistogram<X, K, S, C>
+where
+    X: Hash + Eq,
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+    C: Count,
+{
+    fn default() -> Self {
+        Self {
+            contexts: HashMap::default(),
+        }
+    }
+}
+
+impl<X, K, S, C> ConditionalHistogram<X, K, S, C>
+where
+    X: Hash + Eq,
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+    C: Count,
+{
+    /// Count `key` in `context`, see [`Histogram::add_ref()`]
+    ///
+    /// # Panics
+    /// If the count of `key` in `context` overflows.
+    pub fn add_ref<'a, 'b, QX, Q>(&mut self, context: &'a QX, key: &'b Q)
+    where
+        X: Borrow<QX> + From<&'a QX>,
+        QX: ?Sized + Hash + Eq,
+        K: Borrow<Q> + From<&'b Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.contexts.entry_ref(context).or_default().add_ref(key);
+    }
+
+    /// Count `key` in `context`, taking ownership of both, see [`Histogram::add_owned()`]
+    ///
+    /// # Panics
+    /// If the count of `key` in `context` overflows.
+    pub fn add_owned(&mut self, context: X, key: K) {
+        self.contexts.entry(context).or_default().add_owned(key);
+    }
+}
+
+impl<X, K, S, C> ConditionalHistogram<X, K, S, C>
+where
+    X: Hash + Eq,
+    K: Hash + Eq,
+    S: BuildHasher,
+    C: Count,
+{
+    /// The Histogram of `key`s counted in `context`, or `None` if nothing was counted there
+    pub fn context<QX>(&self, context: &QX) -> Option<&Histogram<K, S, C>>
+    where
+        X: Borrow<QX>,
+        QX: ?Sized + Hash + Eq,
+    {
+        self.contexts.get(context)
+    }
+
+    /// Number of times `key` was counted in `context`
+    pub fn count<QX, Q>(&self, context: &QX, key: &Q) -> C
+    where
+        X: Borrow<QX>,
+        QX: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.context(context)
+            .map_or(C::ZERO, |hist| hist.count(key))
+    }
+
+    /// Conditional probability `P(key | context)` of `key` given `context`
+    ///
+    /// This is `0.0` for contexts in which nothing was counted.
+    pub fn prob<QX, Q>(&self, context: &QX, key: &Q) -> f64
+    where
+        X: Borrow<QX>,
+        QX: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.context(context)
+            .map_or(0.0, |hist| hist.count_rel(key))
+    }
+
+    /// Number of contexts in which something was counted
+    #[must_use]
+    pub fn num_contexts(&self) -> usize {
+        self.contexts.len()
+    }
+
+    /// Total number of instances counted in all contexts
+    ///
+    /// # Panics
+    /// If the total overflows `C` and the [`OverflowPolicy`](crate::OverflowPolicy) is
+    /// [`OverflowPolicy::Panic`](crate::OverflowPolicy::Panic).
+    #[must_use]
+    pub fn num_instances(&self) -> C {
+        let mut total = C::ZERO;
+        for hist in self.contexts.values() {
+            hist.overflow.add(&mut total, hist.num_instances());
+        }
+        total
+    }
+
+    /// Check if nothing was counted so far
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.contexts.is_empty()
+    }
+
+    /// Iterate over all contexts and their Histograms in arbitrary order
+    pub fn iter(&self) -> impl Iterator<Item = (&X, &Histogram<K, S, C>)> {
+        self.contexts.iter()
+    }
+
+    /// Sum up the counts of every `key` over all contexts
+    ///
+    /// # Panics
+    /// If a summed up count overflows.
+    #[must_use]
+    pub fn marginal(&self) -> Histogram<K, S, C>
+    where
+        K: Clone,
+        S: Default,
+    {
+        let mut marginal = Histogram::default();
+        for hist in self.contexts.values() {
+            marginal.extend_from_counts(hist.iter().map(|(key, cnt)| (key.clone(), cnt)));
+        }
+        marginal
+    }
+
+    /// Number of instances counted in every context
+    #[must_use]
+    pub fn context_counts(&self) -> Histogram<X, S, C>
+    where
+        X: Clone,
+        S: Default,
+    {
+        self.contexts
+            .iter()
+            .map(|(context, hist)| (context.clone(), hist.num_instances()))
+            .collect()
+    }
+}
@@ -0,0 +1,159 @@
+//! Counting per document to compute document frequencies and TF-IDF.
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+
+use crate::{Count, DefaultHashBuilder, Histogram};
+
+/// A collection of per-document Histograms that tracks in how many documents every `key` occurs
+///
+/// This is the basis of TF-IDF, which scores how characteristic a `key` is for a document: it is
+/// high for `key`s that are common in the document, but rare in the rest of the corpus.
+///
+/// # Example
+/// ```rust
+/// use histongram::{Corpus, Histogram};
+///
+/// let mut corpus = Corpus::new();
+/// for text in ["the cat sat", "the dog sat", "the cat ate the fish"] {
+///     corpus.add_document(text.split(' ').collect::<Histogram<&str>>());
+/// }
+///
+/// assert_eq!(corpus.document_frequency("the"), 3);
+/// assert_eq!(corpus.tf_idf(0, "the"), 0.0);
+/// assert!(corpus.tf_idf(2, "fish") > corpus.tf_idf(2, "cat"));
+///
+/// let top = corpus.top_tf_idf(1, 1);
+/// assert_eq!(top[0].0, &"dog");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Corpus<K: Hash + Eq, S: BuildHasher = DefaultHashBuilder, C: Count = usize> {
+    docs: Vec<Histogram<K, S, C>>,
+    /// Number of documents every `key` occurs in
+    doc_freq: Histogram<K, S, usize>,
+}
+
+impl<K: Hash + Eq> Corpus<K> {
+    /// Create a new empty `Corpus`
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher + Default, C: Count> Default for Corpus<K, S, C> {
+    fn default() -> Self {
+        Self {
+            docs: Vec::new(),
+            doc_freq: Histogram::default(),
+        }
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher, C: Count> Corpus<K, S, C> {
+    /// Add the counts of a document, returning its index
+    pub fn add_document(&mut self, doc: Histogram<K, S, C>) -> usize
+    where
+        K: Clone,
+    {
+        for key in doc.keys() {
+            if let Some(cnt) = self.doc_freq.map.get_mut(key) {
+                *cnt += 1;
+            } else {
+                self.doc_freq.map.insert(key.clone(), 1);
+            }
+        }
+
+        self.docs.push(doc);
+        self.docs.len() - 1
+    }
+
+    /// The counts of the document with index `doc`, or `None` if there is no such document
+    #[must_use]
+    pub fn document(&self, doc: usize) -> Option<&Histogram<K, S, C>> {
+        self.docs.get(doc)
+    }
+
+    /// Iterate over the counts of all documents in the order they were added
+    pub fn documents(&self) -> impl Iterator<Item = &Histogram<K, S, C>> {
+        self.docs.iter()
+    }
+
+    /// Number of documents in the corpus
+    #[must_use]
+    pub fn num_documents(&self) -> usize {
+        self.docs.len()
+    }
+
+    /// Number of documents `key` occurs in at least once
+    pub fn document_frequency<Q>(&self, key: &Q) -> usize
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        self.doc_freq.count(key)
+    }
+
+    /// The number of documents every `key` occurs in
+    #[must_use]
+    pub fn document_frequencies(&self) -> &Histogram<K, S, usize> {
+        &self.doc_freq
+    }
+
+    /// Inverse document frequency `ln(N / df)` of `key`, where `N` is the number of documents and
+    /// `df` the number of documents containing `key`
+    ///
+    /// `key`s that occur in every document get `0.0`. `key`s that occur in no document can not
+    /// be scored and also get `0.0`.
+    pub fn idf<Q>(&self, key: &Q) -> f64
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        idf(self.num_documents(), self.document_frequency(key))
+    }
+
+    /// TF-IDF of `key` in the document with index `doc`
+    ///
+    /// The term frequency is the relative count of `key` in the document, see
+    /// [`Histogram::count_rel()`]. Unknown documents give `0.0`.
+    pub fn tf_idf<Q>(&self, doc: usize, key: &Q) -> f64
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        self.document(doc)
+            .map_or(0.0, |hist| hist.count_rel(key) * self.idf(key))
+    }
+
+    /// The `k` `key`s of the document with index `doc` with the highest TF-IDF, sorted descending
+    ///
+    /// The order of `key`s with the same score is arbitrary. Unknown documents give an empty
+    /// vector.
+    #[must_use]
+    pub fn top_tf_idf(&self, doc: usize, k: usize) -> Vec<(&K, f64)> {
+        let Some(hist) = self.document(doc) else {
+            return Vec::new();
+        };
+
+        let mut scores: Vec<_> = hist
+            .iter_rel()
+            .map(|(key, tf)| (key, tf * self.idf(key)))
+            .collect();
+        // NOTE: unstable is okay here, as the map order is already arbitrary
+        scores.sort_unstable_by(|(_, a), (_, b)| b.total_cmp(a));
+        scores.truncate(k);
+        scores
+    }
+}
+
+/// Inverse document frequency of a `key` occurring in `df` of `n` documents
+fn idf(n: usize, df: usize) -> f64 {
+    if df == 0 {
+        return 0.0;
+    }
+    // Rounding is fine when the numbers get to large to fit f64
+    #[allow(clippy::cast_precision_loss)]
+    let ratio = n as f64 / df as f64;
+    ratio.ln()
+}
@@ -0,0 +1,180 @@
+//! The [`Count`] trait for the types a [`Histogram`](crate::Histogram) can count with.
+
+use core::cmp::Ordering;
+use core::fmt::{self, Debug, Display};
+use core::iter::Sum;
+use core::ops::{Add, AddAssign};
+
+/// A type that can be used to count occurrences in a [`Histogram`](crate::Histogram)
+///
+/// By default a `Histogram` counts using [`usize`]. Smaller unsigned integers such as [`u32`] can
+/// be used to save memory for huge vocabularies, while [`f32`] and [`f64`] allow for weighted or
+/// fractional counts.
+///
+/// # Example
+/// ```rust
+/// use histongram::{DefaultHashBuilder, Histogram};
+///
+/// let mut hist = Histogram::<char, DefaultHashBuilder, u32>::default();
+/// hist.extend_from_owned("aab".chars());
+///
+/// let count: u32 = hist.count(&'a');
+/// assert_eq!(count, 2);
+/// ```
+pub trait Count: Copy + Debug + PartialOrd + Add<Output = Self> + AddAssign + Sum {
+    /// The count of a `key` that never occurred
+    const ZERO: Self;
+    /// The count of a single occurrence
+    const ONE: Self;
+
+    /// Add `rhs`, returning `None` if the result does not fit into `Self`
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    /// Add `rhs`, staying at the maximum value of `Self` instead of overflowing
+    #[must_use]
+    fn saturating_add(self, rhs: Self) -> Self;
+
+    /// Subtract `rhs`, but never go below [`Count::ZERO`]
+    #[must_use]
+    fn saturating_sub(self, rhs: Self) -> Self;
+
+    /// Convert into a [`f64`], rounding if the count does not fit exactly
+    fn to_f64(self) -> f64;
+
+    /// Total order of counts, used for sorting
+    fn total_cmp(&self, other: &Self) -> Ordering;
+}
+
+macro_rules! impl_count_for_unsigned {
+    ($($ty:ty),*) => {$(
+        impl Count for $ty {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$ty>::checked_add(self, rhs)
+            }
+
+            fn saturating_add(self, rhs: Self) -> Self {
+                <$ty>::saturating_add(self, rhs)
+            }
+
+            fn saturating_sub(self, rhs: Self) -> Self {
+                <$ty>::saturating_sub(self, rhs)
+            }
+
+            // Rounding is fine when the numbers get to large to fit f64
+            #[allow(clippy::cast_precision_loss, clippy::cast_lossless)]
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                self.cmp(other)
+            }
+        }
+    )*};
+}
+
+impl_count_for_unsigned!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_count_for_float {
+    ($($ty:ty),*) => {$(
+        impl Count for $ty {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+
+            // Floating point numbers do not wrap around, at worst they become infinite
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                Some(self + rhs)
+            }
+
+            fn saturating_add(self, rhs: Self) -> Self {
+                self + rhs
+            }
+
+            fn saturating_sub(self, rhs: Self) -> Self {
+                (self - rhs).max(0.0)
+            }
+
+            fn to_f64(self) -> f64 {
+                f64::from(self)
+            }
+
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                <$ty>::total_cmp(self, other)
+            }
+        }
+    )*};
+}
+
+impl_count_for_float!(f32, f64);
+
+/// What a [`Histogram`](crate::Histogram) does if a count does not fit into its [`Count`] type
+///
+/// This applies to all operations that increase counts, such as
+/// [`Histogram::add_owned()`](crate::Histogram::add_owned) or
+/// [`Histogram::append()`](crate::Histogram::append), and to totals summed up from them, such as
+/// [`Histogram::num_instances()`](crate::Histogram::num_instances). Neither counts nor totals
+/// ever silently wrap around, not even in release builds. Relative frequencies are computed in
+/// `f64`, so they are correct even if the total does not fit into the [`Count`] type. Use
+/// [`Histogram::try_add_owned()`](crate::Histogram::try_add_owned) and
+/// [`Histogram::try_add_ref()`](crate::Histogram::try_add_ref) to handle overflows as errors.
+///
+/// # Example
+/// ```rust
+/// use histongram::{DefaultHashBuilder, Histogram, OverflowPolicy};
+///
+/// let mut hist = Histogram::<char, DefaultHashBuilder, u8>::default();
+/// hist.set_overflow_policy(OverflowPolicy::Saturate);
+///
+/// hist.extend_from_owned(std::iter::repeat_n('a', 300));
+/// hist.extend_from_owned(std::iter::repeat_n('b', 100));
+/// assert_eq!(hist.count(&'a'), u8::MAX);
+/// assert_eq!(hist.num_instances(), u8::MAX);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub enum OverflowPolicy {
+    /// Panic when a count overflows
+    #[default]
+    Panic,
+    /// Keep the count at the maximum value of the [`Count`] type
+    Saturate,
+}
+
+impl OverflowPolicy {
+    /// Add `rhs` to `cnt` following this policy
+    pub(crate) fn add<C: Count>(self, cnt: &mut C, rhs: C) {
+        *cnt = match self {
+            Self::Panic => match cnt.checked_add(rhs) {
+                Some(sum) => sum,
+                None => panic!("count overflowed, adding {rhs:?} to {cnt:?}"),
+            },
+            Self::Saturate => cnt.saturating_add(rhs),
+        };
+    }
+
+    /// Sum up `counts` following this policy
+    pub(crate) fn sum<C: Count>(self, counts: impl IntoIterator<Item = C>) -> C {
+        counts.into_iter().fold(C::ZERO, |mut total, cnt| {
+            self.add(&mut total, cnt);
+            total
+        })
+    }
+}
+
+/// Error returned if a count does not fit into its [`Count`] type anymore
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CountOverflow;
+
+impl Display for CountOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("count overflowed")
+    }
+}
+
+impl core::error::Error for CountOverflow {}
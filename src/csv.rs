@@ -0,0 +1,248 @@
+//! Import and export of [`Histogram`]s as CSV files with `key,count` rows.
+
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::hash::{BuildHasher, Hash};
+use std::io::{Read, Write};
+use std::str::FromStr;
+
+use crate::{Count, Histogram, SortOrder};
+
+/// Options for [`Histogram::to_csv_writer()`] and [`Histogram::from_csv_reader()`]
+///
+/// The default writes and expects a `key,count` header row.
+#[cfg_attr(docsrs, doc(cfg(feature = "csv")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Whether the first row is a `key,count` header
+    pub header: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self { header: true }
+    }
+}
+
+/// Error of [`Histogram::to_csv_writer()`] and [`Histogram::from_csv_reader()`]
+#[cfg_attr(docsrs, doc(cfg(feature = "csv")))]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CsvError {
+    /// Reading, writing or parsing the CSV itself failed
+    Csv(csv::Error),
+    /// A row does not have exactly a `key` and a count column
+    InvalidRow {
+        /// Line of the row, starting at 1
+        line: u64,
+    },
+    /// The `key` of a row could not be parsed
+    InvalidKey {
+        /// Line of the row, starting at 1
+        line: u64,
+        /// The `key` as found in the file
+        key: String,
+    },
+    /// The count of a row could not be parsed
+    InvalidCount {
+        /// Line of the row, starting at 1
+        line: u64,
+        /// The count as found in the file
+        count: String,
+    },
+    /// Summing up the counts of a repeated `key` overflows the count type
+    CountOverflow {
+        /// Line of the row that overflowed, starting at 1
+        line: u64,
+    },
+}
+
+impl Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Csv(err) => write!(f, "{err}"),
+            CsvError::InvalidRow { line } => {
+                write!(f, "line {line}: expected exactly two columns `key,count`")
+            }
+            CsvError::InvalidKey { line, key } => write!(f, "line {line}: invalid key {key:?}"),
+            CsvError::InvalidCount { line, count } => {
+                write!(f, "line {line}: invalid count {count:?}")
+            }
+            CsvError::CountOverflow { line } => {
+                write!(f, "line {line}: the summed up count of the key overflows")
+            }
+        }
+    }
+}
+
+impl Error for CsvError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CsvError::Csv(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<csv::Error> for CsvError {
+    fn from(err: csv::Error) -> Self {
+        CsvError::Csv(err)
+    }
+}
+
+impl<K, S, C> Histogram<K, S, C>
+where
+    K: Hash + Eq + Display,
+    S: BuildHasher,
+    C: Count + Display,
+{
+    /// Write the Histogram to `w` as CSV with one `key,count` row per category
+    ///
+    /// `key`s are quoted as needed, so they may contain commas or newlines. The rows are in the
+    /// arbitrary order of the Histogram, use [`Histogram::to_sorted_csv_writer()`] for a stable
+    /// order.
+    ///
+    /// # Errors
+    /// If writing to `w` fails.
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::{CsvOptions, Histogram};
+    /// let hist: Histogram<String> = ["b", "b"].into_iter().collect();
+    ///
+    /// let mut buf = Vec::new();
+    /// hist.to_csv_writer(&mut buf, &CsvOptions::default()).unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buf).unwrap(), "key,count\nb,2\n");
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "csv")))]
+    pub fn to_csv_writer<W: Write>(&self, w: W, options: &CsvOptions) -> Result<(), CsvError> {
+        write_csv(self.map.iter(), w, options.header)
+    }
+
+    /// Write the Histogram to `w` as CSV like [`Histogram::to_csv_writer()`], with the rows sorted
+    /// in `order`
+    ///
+    /// # Errors
+    /// If writing to `w` fails.
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::{CsvOptions, Histogram, SortOrder};
+    /// let hist: Histogram<String> = ["b", "a", "b"].into_iter().collect();
+    ///
+    /// let mut buf = Vec::new();
+    /// hist.to_sorted_csv_writer(&mut buf, &CsvOptions::default(), SortOrder::CountDescending)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buf).unwrap(), "key,count\nb,2\na,1\n");
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "csv")))]
+    pub fn to_sorted_csv_writer<W: Write>(
+        &self,
+        w: W,
+        options: &CsvOptions,
+        order: SortOrder,
+    ) -> Result<(), CsvError>
+    where
+        K: Ord,
+    {
+        let mut rows: Vec<_> = self.map.iter().collect();
+        rows.sort_unstable_by(|a, b| order.compare(*a, *b));
+        write_csv(rows, w, options.header)
+    }
+}
+
+impl<K, S, C> Histogram<K, S, C>
+where
+    K: Hash + Eq + FromStr,
+    S: BuildHasher + Default,
+    C: Count + FromStr,
+{
+    /// Read a Histogram from CSV with one `key,count` row per category, like written by
+    /// [`Histogram::to_csv_writer()`]
+    ///
+    /// The counts of repeated `key`s are summed up. Rows with a count of zero are skipped. The
+    /// rows are read one by one, so only the categories are kept in memory.
+    ///
+    /// # Errors
+    /// If reading from `r` fails, a row does not have two columns, its `key` or count can not be
+    /// parsed, its count is negative or NaN, or summing up the counts of a repeated `key`
+    /// overflows.
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::{CsvOptions, Histogram};
+    ///
+    /// let csv = "word,n\nthe,3\na,2\nthe,1\n";
+    /// let hist = Histogram::<String>::from_csv_reader(csv.as_bytes(), &CsvOptions::default())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(hist.count("the"), 4);
+    /// assert_eq!(hist.count("a"), 2);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "csv")))]
+    pub fn from_csv_reader<R: Read>(r: R, options: &CsvOptions) -> Result<Self, CsvError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(options.header)
+            .flexible(true)
+            .from_reader(r);
+
+        let mut hist = Self::default();
+        for record in reader.records() {
+            let record = record?;
+            let line = record.position().map_or(0, csv::Position::line);
+            let (Some(key), Some(count), 2) = (record.get(0), record.get(1), record.len()) else {
+                return Err(CsvError::InvalidRow { line });
+            };
+
+            let key = key.parse().map_err(|_| CsvError::InvalidKey {
+                line,
+                key: key.to_string(),
+            })?;
+            let invalid_count = || CsvError::InvalidCount {
+                line,
+                count: count.to_string(),
+            };
+            let count: C = count.trim().parse().map_err(|_| invalid_count())?;
+            match count.partial_cmp(&C::ZERO) {
+                Some(Ordering::Greater) => {}
+                Some(Ordering::Equal) => continue,
+                // Negative or NaN
+                Some(Ordering::Less) | None => return Err(invalid_count()),
+            }
+
+            if let Some(cnt) = hist.map.get_mut(&key) {
+                *cnt = cnt
+                    .checked_add(count)
+                    .ok_or(CsvError::CountOverflow { line })?;
+            } else {
+                hist.map.insert(key, count);
+            }
+        }
+        Ok(hist)
+    }
+}
+
+/// Write `key,count` rows to `w`, after a header row if `header` is set
+fn write_csv<'a, K, C, W>(
+    rows: impl IntoIterator<Item = (&'a K, &'a C)>,
+    w: W,
+    header: bool,
+) -> Result<(), CsvError>
+where
+    K: Display + 'a,
+    C: Display + 'a,
+    W: Write,
+{
+    let mut writer = csv::Writer::from_writer(w);
+    if header {
+        writer.write_record(["key", "count"])?;
+    }
+    for (key, cnt) in rows {
+        writer.write_record([key.to_string(), cnt.to_string()])?;
+    }
+    writer.flush().map_err(csv::Error::from)?;
+    Ok(())
+}
@@ -0,0 +1,202 @@
+//! A [`Histogram`] whose counts fade out over time, to track what is common recently.
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+
+use hashbrown::HashMap;
+
+use crate::{DefaultHashBuilder, Histogram};
+
+/// Rescale the stored counts once the weight of new instances gets this large, long before
+/// `f64` runs out of range
+const RESCALE_ABOVE: f64 = 1e100;
+
+/// A Histogram whose counts decay exponentially with a configurable half-life
+///
+/// Time does not pass on its own, instead it is advanced explicitly with
+/// [`DecayingHistogram::advance()`], in whatever unit the half-life is given in. After one
+/// half-life has passed, every count is halved. Counts are `f64`, as they are fractional after
+/// decaying.
+///
+/// Internally the counts are not touched when time passes, instead new instances are weighted
+/// higher. So advancing time is `O(1)` and the other operations are as fast as for a
+/// [`Histogram`].
+///
+/// # Example
+/// ```rust
+/// use histongram::DecayingHistogram;
+///
+/// // Counts halve every 60 seconds
+/// let mut recent = DecayingHistogram::<String>::new(60.0);
+///
+/// recent.add_ref("old");
+/// recent.advance(120.0);
+/// recent.add_ref("new");
+///
+/// assert_eq!(recent.count("old"), 0.25);
+/// assert_eq!(recent.count("new"), 1.0);
+/// assert_eq!(recent.top_k(1), vec![(&"new".to_string(), 1.0)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DecayingHistogram<K: Hash + Eq, S: BuildHasher = DefaultHashBuilder> {
+    /// Counts scaled up by `weight` at the time they were added
+    inner: Histogram<K, S, f64>,
+    half_life: f64,
+    /// How much an instance added now weighs compared to the stored counts
+    weight: f64,
+}
+
+impl<K: Hash + Eq> DecayingHistogram<K> {
+    /// Create a new empty `DecayingHistogram` whose counts halve every `half_life`
+    ///
+    /// # Panics
+    /// If `half_life` is not a positive finite number.
+    #[must_use]
+    pub fn new(half_life: f64) -> Self {
+        Self::with_hasher(half_life, DefaultHashBuilder::default())
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher> DecayingHistogram<K, S> {
+    /// Create a new empty `DecayingHistogram` using the given `hash_builder`
+    ///
+    /// # Panics
+    /// If `half_life` is not a positive finite number.
+    #[must_use]
+    pub fn with_hasher(half_life: f64, hash_builder: S) -> Self {
+        assert!(
+            half_life > 0.0 && half_life.is_finite(),
+            "half-life must be positive and finite, got {half_life}"
+        );
+
+        Self {
+            inner: HashMap::with_hasher(hash_builder).into(),
+            half_life,
+            weight: 1.0,
+        }
+    }
+
+    /// The time after which counts are halved
+    #[must_use]
+    pub fn half_life(&self) -> f64 {
+        self.half_life
+    }
+
+    /// Let `elapsed` time pass, decaying all counts by `0.5^(elapsed / half_life)`
+    ///
+    /// # Panics
+    /// If `elapsed` is negative or NaN.
+    pub fn advance(&mut self, elapsed: f64) {
+        assert!(elapsed >= 0.0, "time can not go backwards, got {elapsed}");
+
+        self.weight *= (elapsed / self.half_life).exp2();
+        if self.weight > RESCALE_ABOVE {
+            let scale = self.weight.recip();
+            for cnt in self.inner.map.values_mut() {
+                *cnt *= scale;
+            }
+            // Counts that decayed below the smallest `f64` are gone, the same as when pruned. If
+            // so much time passed that `weight` is infinite, this removes every category.
+            self.inner.map.retain(|_key, cnt| *cnt > 0.0);
+            self.weight = 1.0;
+        }
+    }
+
+    /// Add a single occurrence of `val`, see [`Histogram::add_ref()`]
+    pub fn add_ref<'a, Q>(&mut self, val: &'a Q)
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q> + From<&'a Q>,
+    {
+        self.inner.add_ref_n(val, self.weight);
+    }
+
+    /// Add a single occurrence of `val`, see [`Histogram::add_owned()`]
+    pub fn add_owned(&mut self, val: K) {
+        self.inner.add_owned_n(val, self.weight);
+    }
+
+    /// Add `n` occurrences of `val`, see [`Histogram::add_owned_n()`]
+    pub fn add_owned_n(&mut self, val: K, n: f64) {
+        self.inner.add_owned_n(val, n * self.weight);
+    }
+
+    /// Current, decayed count of `key`, see [`Histogram::count()`]
+    pub fn count<Q>(&self, key: &Q) -> f64
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        self.inner.count(key) / self.weight
+    }
+
+    /// Number of categories, including those that have decayed to almost nothing
+    ///
+    /// Use [`DecayingHistogram::prune_below()`] to forget about those.
+    #[must_use]
+    pub fn num_categories(&self) -> usize {
+        self.inner.num_categories()
+    }
+
+    /// Sum of all current, decayed counts
+    #[must_use]
+    pub fn num_instances(&self) -> f64 {
+        self.inner.num_instances() / self.weight
+    }
+
+    /// Check if nothing was added so far, or everything was pruned
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Iterate over all `key`s and their current, decayed counts
+    ///
+    /// The order of keys is arbitrary.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, f64)> {
+        let weight = self.weight;
+        self.inner.iter().map(move |(key, cnt)| (key, cnt / weight))
+    }
+
+    /// Get the `k` `key`s with the highest current counts, see [`Histogram::top_k()`]
+    #[must_use]
+    pub fn top_k(&self, k: usize) -> Vec<(&K, f64)> {
+        let weight = self.weight;
+        let mut top = self.inner.top_k(k);
+        for (_key, cnt) in &mut top {
+            *cnt /= weight;
+        }
+        top
+    }
+
+    /// Remove all categories whose current count has decayed below `min_count`
+    ///
+    /// Without pruning every `key` ever seen is kept, so call this now and then in long-running
+    /// services.
+    pub fn prune_below(&mut self, min_count: f64) {
+        self.inner.prune_below(min_count * self.weight);
+    }
+
+    /// Copy the current, decayed counts into a regular [`Histogram`]
+    #[must_use]
+    pub fn snapshot(&self) -> Histogram<K, S, f64>
+    where
+        K: Clone,
+        S: Clone,
+    {
+        let mut hist = self.inner.clone();
+        for cnt in hist.map.values_mut() {
+            *cnt /= self.weight;
+        }
+        hist
+    }
+
+    /// Convert into a regular [`Histogram`] of the current, decayed counts
+    #[must_use]
+    pub fn into_histogram(mut self) -> Histogram<K, S, f64> {
+        for cnt in self.inner.map.values_mut() {
+            *cnt /= self.weight;
+        }
+        self.inner
+    }
+}
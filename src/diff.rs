@@ -0,0 +1,79 @@
+//! Reporting what changed between two [`Histogram`]s, e.g. two versions of a corpus.
+
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash};
+
+use crate::{Count, Histogram};
+
+/// What changed between two Histograms, created by [`Histogram::diff()`]
+///
+/// The `key`s are borrowed from the Histograms. Within every list the order is arbitrary, sort
+/// them for a stable report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramDiff<'a, K, C> {
+    /// `key`s only in the new Histogram, with their count
+    pub added: Vec<(&'a K, C)>,
+    /// `key`s only in the old Histogram, with their count
+    pub removed: Vec<(&'a K, C)>,
+    /// `key`s in both Histograms with a different count, with the old and the new count
+    pub changed: Vec<(&'a K, C, C)>,
+    /// Number of `key`s in both Histograms with the same count
+    pub unchanged: usize,
+    /// Sum of all counts of the old Histogram
+    pub old_instances: C,
+    /// Sum of all counts of the new Histogram
+    pub new_instances: C,
+}
+
+impl<K, C: Count> HistogramDiff<'_, K, C> {
+    /// Check if both Histograms have exactly the same counts
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher, C: Count> Histogram<K, S, C> {
+    /// Compare `self` as the old version against `other` as the new one
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let v1 = Histogram::<_>::from_owned_iter("aabc".chars());
+    /// let v2 = Histogram::<_>::from_owned_iter("abbbd".chars());
+    ///
+    /// let diff = v1.diff(&v2);
+    /// assert_eq!(diff.added, vec![(&'d', 1)]);
+    /// assert_eq!(diff.removed, vec![(&'c', 1)]);
+    ///
+    /// let mut changed = diff.changed.clone();
+    /// changed.sort_unstable();
+    /// assert_eq!(changed, vec![(&'a', 2, 1), (&'b', 1, 3)]);
+    /// assert_eq!((diff.old_instances, diff.new_instances), (4, 5));
+    /// ```
+    #[must_use]
+    pub fn diff<'a, S2: BuildHasher>(
+        &'a self,
+        other: &'a Histogram<K, S2, C>,
+    ) -> HistogramDiff<'a, K, C> {
+        let mut diff = HistogramDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+            unchanged: 0,
+            old_instances: self.num_instances(),
+            new_instances: other.num_instances(),
+        };
+
+        for (key, old) in self {
+            match other.map.get(key) {
+                None => diff.removed.push((key, old)),
+                Some(&new) if new.total_cmp(&old).is_eq() => diff.unchanged += 1,
+                Some(&new) => diff.changed.push((key, old, new)),
+            }
+        }
+        diff.added
+            .extend(other.iter().filter(|(key, _)| !self.contains_key(*key)));
+        diff
+    }
+}
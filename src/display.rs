@@ -0,0 +1,121 @@
+//! Printing a [`Histogram`] as a table with ASCII bars for quick inspection in a terminal.
+
+use std::fmt::{self, Display, Write};
+use std::hash::{BuildHasher, Hash};
+
+use crate::{Count, Histogram};
+
+/// Width of the bars if [`Display`] is used without a width
+const DEFAULT_BAR_WIDTH: usize = 40;
+
+impl<K, S, C> Histogram<K, S, C>
+where
+    K: Hash + Eq + Display,
+    S: BuildHasher,
+    C: Count + Display,
+{
+    /// Render the `top_n` most common `key`s as a table with bars of at most `width` characters
+    ///
+    /// Every row contains the `key`, its count and a bar of `#`s proportional to the count, the
+    /// most common `key` getting the full `width`. If `key`s are left out, a last row tells how
+    /// many. The order of `key`s with the same count is arbitrary.
+    ///
+    /// The [`Display`] implementation renders all `key`s like this, using the width given in the
+    /// format string for the bars or 40 characters by default.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist: Histogram<_> = Histogram::from_owned_iter("aaaabbc".chars());
+    ///
+    /// assert_eq!(hist.render(8, 2), "\
+    /// a  4  ########
+    /// b  2  ####
+    /// … and 1 more
+    /// ");
+    /// assert_eq!(format!("{hist:4}"), "\
+    /// a  4  ####
+    /// b  2  ##
+    /// c  1  #
+    /// ");
+    /// ```
+    #[must_use]
+    pub fn render(&self, width: usize, top_n: usize) -> String {
+        Chart {
+            hist: self,
+            width,
+            top_n,
+        }
+        .to_string()
+    }
+
+    fn write_chart<W: Write>(&self, w: &mut W, width: usize, top_n: usize) -> fmt::Result {
+        let rows: Vec<_> = self
+            .top_k(top_n)
+            .into_iter()
+            .map(|(key, cnt)| (key.to_string(), cnt.to_string(), cnt.to_f64()))
+            .collect();
+
+        let key_width = rows.iter().map(|(key, ..)| key.chars().count()).max();
+        let count_width = rows.iter().map(|(_, cnt, _)| cnt.len()).max();
+        let max = rows.first().map_or(0.0, |(.., cnt)| *cnt);
+
+        for (key, cnt, value) in &rows {
+            // The ratio is between 0 and 1, so the length is at most `width`, and rounding is
+            // fine when the numbers get to large to fit f64
+            #[allow(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                clippy::cast_precision_loss
+            )]
+            let bar = if max > 0.0 {
+                (value / max * width as f64).round() as usize
+            } else {
+                0
+            };
+            writeln!(
+                w,
+                "{key:<key_width$}  {cnt:>count_width$}  {}",
+                "#".repeat(bar),
+                key_width = key_width.unwrap_or(0),
+                count_width = count_width.unwrap_or(0),
+            )?;
+        }
+
+        let hidden = self.num_categories() - rows.len();
+        if hidden > 0 {
+            writeln!(w, "… and {hidden} more")?;
+        }
+        Ok(())
+    }
+}
+
+impl<K, S, C> Display for Histogram<K, S, C>
+where
+    K: Hash + Eq + Display,
+    S: BuildHasher,
+    C: Count + Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = f.width().unwrap_or(DEFAULT_BAR_WIDTH);
+        self.write_chart(f, width, self.num_categories())
+    }
+}
+
+/// Renders a Histogram through [`Display`], to build [`Histogram::render()`] on `to_string()`
+struct Chart<'a, K: Hash + Eq, S: BuildHasher, C: Count> {
+    hist: &'a Histogram<K, S, C>,
+    width: usize,
+    top_n: usize,
+}
+
+impl<K, S, C> Display for Chart<'_, K, S, C>
+where
+    K: Hash + Eq + Display,
+    S: BuildHasher,
+    C: Count + Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.hist.write_chart(f, self.width, self.top_n)
+    }
+}
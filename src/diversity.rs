@@ -0,0 +1,109 @@
+//! Diversity indices of a [`Histogram`], as commonly used in ecology and corpus linguistics.
+
+use std::hash::{BuildHasher, Hash};
+
+use crate::{Count, Histogram};
+
+impl<K: Hash + Eq, S: BuildHasher, C: Count> Histogram<K, S, C> {
+    /// Simpson's index, the probability that two instances drawn with replacement are the same
+    /// `key`
+    ///
+    /// This is the sum of the squared relative frequencies. It is `1.0` if there is only one
+    /// category and approaches `0.0` for many equally common categories. An empty Histogram has
+    /// an index of `0.0`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("abcd".chars());
+    /// assert_eq!(hist.simpson_index(), 0.25);
+    /// ```
+    #[must_use]
+    pub fn simpson_index(&self) -> f64 {
+        self.iter_rel().map(|(_key, p)| p * p).sum()
+    }
+
+    /// Gini-Simpson index, the probability that two instances drawn with replacement are different
+    /// `key`s
+    ///
+    /// This is `1.0 - simpson_index()`, so higher values mean more diversity. An empty Histogram
+    /// has an index of `0.0`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("abcd".chars());
+    /// assert_eq!(hist.gini_simpson_index(), 0.75);
+    /// ```
+    #[must_use]
+    pub fn gini_simpson_index(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        1.0 - self.simpson_index()
+    }
+
+    /// Gini coefficient of the counts, measuring how unequally the instances are distributed
+    ///
+    /// It is `0.0` if all `key`s have the same count and approaches `1.0` if nearly all instances
+    /// belong to a single `key`. An empty Histogram has a coefficient of `0.0`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let equal = Histogram::<_>::from_owned_iter("aabb".chars());
+    /// assert_eq!(equal.gini_coefficient(), 0.0);
+    ///
+    /// let unequal = Histogram::<_>::from_owned_iter("aaaaaaab".chars());
+    /// assert!(unequal.gini_coefficient() > 0.0);
+    /// ```
+    #[must_use]
+    pub fn gini_coefficient(&self) -> f64 {
+        let total = self.instances_f64();
+        if total == 0.0 {
+            return 0.0;
+        }
+
+        let mut counts: Vec<_> = self.iter().map(|(_key, cnt)| cnt.to_f64()).collect();
+        counts.sort_unstable_by(f64::total_cmp);
+
+        // Rounding is fine when the numbers get to large to fit f64
+        #[allow(clippy::cast_precision_loss)]
+        {
+            let n = counts.len() as f64;
+            let weighted: f64 = counts
+                .iter()
+                .enumerate()
+                .map(|(idx, cnt)| (idx + 1) as f64 * cnt)
+                .sum();
+            (2.0 * weighted / (n * total) - (n + 1.0) / n).max(0.0)
+        }
+    }
+
+    /// Shannon evenness (Pielou's index), how close the counts are to all being equal
+    ///
+    /// This is the [`Histogram::entropy()`] divided by the maximal entropy possible with the same
+    /// number of categories. It lies between `0.0` and `1.0`, where `1.0` means all `key`s are
+    /// equally common. Histograms with less than two categories are treated as perfectly even.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let even = Histogram::<_>::from_owned_iter("aabbcc".chars());
+    /// assert!((even.shannon_evenness() - 1.0).abs() < 1e-12);
+    ///
+    /// let uneven = Histogram::<_>::from_owned_iter("aaaaabc".chars());
+    /// assert!(uneven.shannon_evenness() < 1.0);
+    /// ```
+    #[must_use]
+    pub fn shannon_evenness(&self) -> f64 {
+        if self.num_categories() < 2 {
+            return 1.0;
+        }
+
+        // Rounding is fine when the numbers get to large to fit f64
+        #[allow(clippy::cast_precision_loss)]
+        let max_entropy = (self.num_categories() as f64).log2();
+        (self.entropy() / max_entropy).min(1.0)
+    }
+}
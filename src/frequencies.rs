@@ -0,0 +1,111 @@
+//! Relative frequencies of a [`Histogram`], normalized once for repeated lookups.
+
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+use crate::{Count, Histogram};
+
+/// Relative frequencies of a [`Histogram`], created by [`Histogram::frequencies()`]
+///
+/// [`Histogram::count_rel()`] sums up all counts on every call. This computes the total only
+/// once, so it is the better choice when looking up many probabilities of the same Histogram.
+///
+/// # Example
+/// ```rust
+/// # use histongram::Histogram;
+/// let hist = Histogram::<_>::from_owned_iter("aaab".chars());
+/// let freq = hist.frequencies();
+///
+/// assert_eq!(freq.prob(&'a'), 0.75);
+/// assert_eq!(freq.prob(&'z'), 0.0);
+/// assert_eq!(freq.top_k(1), vec![(&'a', 0.75)]);
+/// ```
+#[derive(Debug)]
+pub struct Frequencies<'a, K: Hash + Eq, S: BuildHasher, C: Count> {
+    hist: &'a Histogram<K, S, C>,
+    /// `1 / num_instances`, or `0.0` for an empty Histogram
+    scale: f64,
+}
+
+// Not derived, as that would needlessly require `K`, `S` and `C` to be `Copy`
+impl<K: Hash + Eq, S: BuildHasher, C: Count> Clone for Frequencies<'_, K, S, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher, C: Count> Copy for Frequencies<'_, K, S, C> {}
+
+impl<'a, K: Hash + Eq, S: BuildHasher, C: Count> Frequencies<'a, K, S, C> {
+    /// Relative frequency of `key`, see [`Histogram::count_rel()`]
+    #[must_use]
+    pub fn prob<Q>(&self, key: &Q) -> f64
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.hist.count(key).to_f64() * self.scale
+    }
+
+    /// Iterate over all `key`s and their relative frequencies
+    ///
+    /// The order of keys is arbitrary.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a K, f64)> {
+        let scale = self.scale;
+        self.hist
+            .iter()
+            .map(move |(key, cnt)| (key, cnt.to_f64() * scale))
+    }
+
+    /// Get the `k` most common `key`s and their relative frequencies, see [`Histogram::top_k()`]
+    #[must_use]
+    pub fn top_k(&self, k: usize) -> Vec<(&'a K, f64)> {
+        self.hist
+            .top_k(k)
+            .into_iter()
+            .map(|(key, cnt)| (key, cnt.to_f64() * self.scale))
+            .collect()
+    }
+
+    /// Shannon entropy in bits, see [`Histogram::entropy()`]
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn entropy(&self) -> f64 {
+        // Every category occurred at least once, so `p > 0` and `log2(p)` is finite
+        self.iter().fold(0.0, |h, (_key, p)| h - p * p.log2())
+    }
+
+    /// Number of `key`s with a non-zero frequency
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.hist.num_categories()
+    }
+
+    /// Check if there are no `key`s, i.e. the Histogram is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.hist.is_empty()
+    }
+
+    /// The underlying Histogram
+    #[must_use]
+    pub fn histogram(&self) -> &'a Histogram<K, S, C> {
+        self.hist
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher, C: Count> Histogram<K, S, C> {
+    /// Relative frequencies of all `key`s, normalized once
+    ///
+    /// The frequencies sum up to `1.0`, unless the Histogram is empty. Use this instead of
+    /// [`Histogram::count_rel()`] for many lookups, see [`Frequencies`].
+    #[must_use]
+    pub fn frequencies(&self) -> Frequencies<'_, K, S, C> {
+        let total = self.instances_f64();
+        Frequencies {
+            hist: self,
+            scale: if total == 0.0 { 0.0 } else { total.recip() },
+        }
+    }
+}
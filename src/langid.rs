@@ -0,0 +1,239 @@
+//! Identifying the language of a text by its character n-grams.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+use hashbrown::HashMap;
+
+use crate::{Count, Histogram};
+
+/// The most common character n-grams of a text, sorted descending by count
+///
+/// With the `serde` feature a profile is (de)serialized as the list of its n-grams, so trained
+/// profiles can be stored and shipped without the sample texts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "Vec<String>", into = "Vec<String>")
+)]
+pub struct LanguageProfile {
+    ngrams: Vec<String>,
+    /// The index of every n-gram in `ngrams`
+    ranks: HashMap<String, usize>,
+}
+
+impl LanguageProfile {
+    /// Build the profile of `text` from its character n-grams of lengths `1..=max_n`, keeping
+    /// the `size` most common ones
+    ///
+    /// Words are lowercased and padded with `_` on both sides, so n-grams at the start and end of
+    /// words are told apart. Everything that is not alphabetic separates words.
+    #[must_use]
+    pub fn from_text(text: &str, max_n: usize, size: usize) -> Self {
+        let mut hist = Histogram::<String>::new();
+        count_char_ngrams(&mut hist, text, max_n);
+        Self::from_histogram(hist, size)
+    }
+
+    /// Build the profile from the `size` most common n-grams of `hist`
+    ///
+    /// N-grams with the same count are ordered by the n-gram, so profiles are deterministic.
+    #[must_use]
+    pub fn from_histogram<S, C>(hist: Histogram<String, S, C>, size: usize) -> Self
+    where
+        S: BuildHasher,
+        C: Count,
+    {
+        let mut ngrams: Vec<String> = hist
+            .into_sorted_iter()
+            .take(size)
+            .map(|(ngram, _cnt)| ngram)
+            .collect();
+        ngrams.shrink_to_fit();
+        ngrams.into()
+    }
+
+    /// The n-grams of the profile, most common first
+    #[must_use]
+    pub fn ngrams(&self) -> &[String] {
+        &self.ngrams
+    }
+
+    /// Number of n-grams in the profile
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ngrams.len()
+    }
+
+    /// Check if the profile has no n-grams
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ngrams.is_empty()
+    }
+
+    /// The "out-of-place" distance of the `text` profile from `self`
+    ///
+    /// For every n-gram of `text` this sums up how far its rank is off from its rank in `self`.
+    /// N-grams missing from `self` count as being off by the length of `self`. Smaller distances
+    /// mean more similar texts.
+    #[must_use]
+    pub fn distance(&self, text: &LanguageProfile) -> usize {
+        text.ngrams
+            .iter()
+            .enumerate()
+            .map(|(rank, ngram)| {
+                self.ranks
+                    .get(ngram)
+                    .map_or(self.len(), |&own| own.abs_diff(rank))
+            })
+            .sum()
+    }
+}
+
+impl From<Vec<String>> for LanguageProfile {
+    /// Use `ngrams` as a profile, which must be sorted descending by count
+    ///
+    /// If an n-gram occurs multiple times, only its first occurrence counts.
+    fn from(ngrams: Vec<String>) -> Self {
+        let mut ranks = HashMap::with_capacity(ngrams.len());
+        for (rank, ngram) in ngrams.iter().enumerate() {
+            ranks.entry(ngram.clone()).or_insert(rank);
+        }
+        Self { ngrams, ranks }
+    }
+}
+
+impl From<LanguageProfile> for Vec<String> {
+    fn from(profile: LanguageProfile) -> Self {
+        profile.ngrams
+    }
+}
+
+/// Classifies texts by comparing their [`LanguageProfile`] against those of known languages
+///
+/// This implements the rank order method by Cavnar and Trenkle: every language is described by a
+/// [`LanguageProfile`], the most common character n-grams of some sample text sorted by count. An
+/// unknown text gets a profile the same way and is assigned the language whose profile ranks the
+/// n-grams most similarly. A few kilobytes of sample text per language are enough to reliably
+/// classify texts of a sentence or more.
+///
+/// `L` is the label of a language, like a language code or an enum.
+///
+/// # Example
+/// ```rust
+/// use histongram::LanguageIdentifier;
+///
+/// let mut langid = LanguageIdentifier::default();
+/// langid.add_language(
+///     "en",
+///     "The quick brown fox jumps over the lazy dog. Then the dog wakes up and chases the fox \
+///      through the garden, while the birds are singing in the trees.",
+/// );
+/// langid.add_language(
+///     "de",
+///     "Der schnelle braune Fuchs springt über den faulen Hund. Dann wacht der Hund auf und \
+///      jagt den Fuchs durch den Garten, während die Vögel in den Bäumen singen.",
+/// );
+///
+/// assert_eq!(langid.classify("The birds are in the garden"), Some(&"en"));
+/// assert_eq!(langid.classify("Die Vögel singen in dem Garten"), Some(&"de"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LanguageIdentifier<L> {
+    languages: Vec<(L, LanguageProfile)>,
+    max_n: usize,
+    profile_size: usize,
+}
+
+impl<L> Default for LanguageIdentifier<L> {
+    /// Use n-grams of lengths 1 to 3 and profiles of 300 n-grams
+    fn default() -> Self {
+        Self::new(3, 300)
+    }
+}
+
+impl<L> LanguageIdentifier<L> {
+    /// Create an identifier without languages, using n-grams of lengths `1..=max_n` and profiles
+    /// of `profile_size` n-grams
+    #[must_use]
+    pub fn new(max_n: usize, profile_size: usize) -> Self {
+        Self {
+            languages: Vec::new(),
+            max_n,
+            profile_size,
+        }
+    }
+
+    /// Learn the profile of `language` from the sample `text`
+    pub fn add_language(&mut self, language: L, text: &str) {
+        let profile = LanguageProfile::from_text(text, self.max_n, self.profile_size);
+        self.add_profile(language, profile);
+    }
+
+    /// Add a previously trained profile of `language`
+    ///
+    /// It should have been built with the same `max_n` and a similar size as this identifier
+    /// uses, otherwise the distances are not comparable.
+    pub fn add_profile(&mut self, language: L, profile: LanguageProfile) {
+        self.languages.push((language, profile));
+    }
+
+    /// All known languages and their profiles, in the order they were added
+    #[must_use]
+    pub fn profiles(&self) -> &[(L, LanguageProfile)] {
+        &self.languages
+    }
+
+    /// The language of `text`, or `None` if no languages are known
+    ///
+    /// If multiple languages are equally close, the one added first wins.
+    #[must_use]
+    pub fn classify(&self, text: &str) -> Option<&L> {
+        self.rank(text)
+            .into_iter()
+            .next()
+            .map(|(language, _)| language)
+    }
+
+    /// All known languages with the distance of `text` to them, closest first
+    #[must_use]
+    pub fn rank(&self, text: &str) -> Vec<(&L, usize)> {
+        let profile = LanguageProfile::from_text(text, self.max_n, self.profile_size);
+        let mut ranked: Vec<_> = self
+            .languages
+            .iter()
+            .map(|(language, own)| (language, own.distance(&profile)))
+            .collect();
+        // Stable, so earlier languages win ties
+        ranked.sort_by_key(|&(_, distance)| distance);
+        ranked
+    }
+}
+
+/// Count all character n-grams of lengths `1..=max_n` of the padded, lowercased words of `text`
+fn count_char_ngrams<K, S, C>(hist: &mut Histogram<K, S, C>, text: &str, max_n: usize)
+where
+    K: Hash + Eq + Borrow<str> + for<'a> From<&'a str>,
+    S: BuildHasher,
+    C: Count,
+{
+    for word in text.split(|c: char| !c.is_alphabetic()) {
+        if word.is_empty() {
+            continue;
+        }
+
+        let padded = format!("_{}_", word.to_lowercase());
+        let mut bounds: Vec<usize> = padded.char_indices().map(|(idx, _)| idx).collect();
+        bounds.push(padded.len());
+
+        for n in 1..=max_n {
+            for window in bounds.windows(n + 1) {
+                hist.add_ref(&padded[window[0]..window[n]]);
+            }
+        }
+    }
+}
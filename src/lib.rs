@@ -2,18 +2,123 @@
 #![warn(clippy::pedantic)]
 #![warn(missing_docs)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(not(feature = "std"), no_std)]
 
 //! A small crate for counting n-grams
+//!
+//! Without the default `std` feature only `alloc` is required. That leaves out everything that
+//! needs floating point functions like logarithms, I/O or threads.
 
-use std::borrow::Borrow;
-use std::cmp::Reverse;
-use std::hash::{BuildHasher, Hash};
-use std::iter;
+extern crate alloc;
+
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::{Ordering, Reverse};
+use core::hash::{BuildHasher, Hash};
+use core::iter;
+use core::ops::{Add, AddAssign, Index, Sub, SubAssign};
 
 use hashbrown::hash_map;
 pub use hashbrown::hash_map::DefaultHashBuilder;
 use hashbrown::HashMap;
 
+mod annotated;
+#[cfg(feature = "proptest")]
+mod arbitrary;
+#[cfg(feature = "rkyv")]
+mod archive;
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "std")]
+mod binary;
+mod bpe;
+mod bucket;
+mod bytes;
+#[cfg(feature = "std")]
+mod cardinality;
+#[cfg(feature = "std")]
+mod compare;
+#[cfg(feature = "std")]
+mod concurrent;
+mod conditional;
+#[cfg(feature = "std")]
+mod corpus;
+mod count;
+#[cfg(feature = "csv")]
+mod csv;
+#[cfg(feature = "std")]
+mod decay;
+mod diff;
+#[cfg(feature = "std")]
+mod display;
+#[cfg(feature = "std")]
+mod diversity;
+mod frequencies;
+mod langid;
+mod ordered;
+#[cfg(feature = "std")]
+mod read;
+#[cfg(feature = "rand")]
+mod reservoir;
+#[cfg(feature = "rand")]
+mod sample;
+mod scale;
+#[cfg(feature = "unicode-segmentation")]
+mod segment;
+#[cfg(feature = "std")]
+mod sketch;
+mod small;
+#[cfg(feature = "std")]
+mod smoothing;
+mod spell;
+#[cfg(feature = "std")]
+pub mod stats;
+mod tokenize;
+mod top_k;
+
+pub use crate::annotated::AnnotatedHistogram;
+#[cfg(feature = "proptest")]
+pub use crate::arbitrary::arb_histogram;
+#[cfg(feature = "arrow")]
+pub use crate::arrow::ArrowCount;
+#[cfg(feature = "std")]
+pub use crate::binary::BinaryFormat;
+pub use crate::bpe::BytePairEncoding;
+pub use crate::bucket::{Bucket, BucketedHistogram};
+#[cfg(feature = "std")]
+pub use crate::cardinality::CardinalityEstimator;
+#[cfg(feature = "std")]
+pub use crate::concurrent::ConcurrentHistogram;
+pub use crate::conditional::ConditionalHistogram;
+#[cfg(feature = "std")]
+pub use crate::corpus::Corpus;
+#[cfg(feature = "rkyv")]
+pub use crate::count::ArchivedOverflowPolicy;
+pub use crate::count::{Count, CountOverflow, OverflowPolicy};
+#[cfg(feature = "csv")]
+pub use crate::csv::{CsvError, CsvOptions};
+#[cfg(feature = "std")]
+pub use crate::decay::DecayingHistogram;
+pub use crate::diff::HistogramDiff;
+pub use crate::frequencies::Frequencies;
+pub use crate::langid::{LanguageIdentifier, LanguageProfile};
+pub use crate::ordered::OrderedHistogram;
+#[cfg(feature = "rand")]
+pub use crate::reservoir::{CountAndSample, ReservoirSampler};
+#[cfg(feature = "rand")]
+pub use crate::sample::SampledDistribution;
+#[cfg(feature = "std")]
+pub use crate::sketch::CountMinSketch;
+pub use crate::small::SmallHistogram;
+#[cfg(feature = "std")]
+pub use crate::smoothing::{GoodTuring, Smoothed};
+pub use crate::spell::SpellChecker;
+#[cfg(feature = "unicode-segmentation")]
+pub use crate::tokenize::UnicodeWords;
+pub use crate::tokenize::{Tokenizer, Whitespace};
+pub use crate::top_k::TopKHistogram;
+
 /// A histogram that counts occurrences of `key`s.
 ///
 /// # Examples
@@ -93,9 +198,18 @@ use hashbrown::HashMap;
 /// hist.add_ref("foo");
 /// assert_eq!(hist.count("foo"), 1);
 /// ```
-#[derive(Debug, Clone, Eq, PartialEq)]
-pub struct Histogram<K: Hash + Eq, S: BuildHasher = DefaultHashBuilder> {
-    map: HashMap<K, usize, S>,
+///
+/// ## Counting with other types than `usize`
+/// See [`Count`] for using smaller integers or floating point numbers as counts, and
+/// [`OverflowPolicy`] for what happens if they get too large.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(::rkyv::Archive, ::rkyv::Serialize, ::rkyv::Deserialize)
+)]
+pub struct Histogram<K: Hash + Eq, S: BuildHasher = DefaultHashBuilder, C: Count = usize> {
+    map: HashMap<K, C, S>,
+    overflow: OverflowPolicy,
 }
 
 impl<K: Hash + Eq> Histogram<K, DefaultHashBuilder> {
@@ -104,10 +218,28 @@ impl<K: Hash + Eq> Histogram<K, DefaultHashBuilder> {
     pub fn new() -> Self {
         Self {
             map: HashMap::default(),
+            overflow: OverflowPolicy::Panic,
         }
     }
 
-    /// Collect the counts from `iter` into a new `Histogram`
+    /// Create a new empty `Histogram` with space for at least `capacity` categories
+    ///
+    /// Pre-sizing the Histogram avoids rehashing while it grows, for example if the size of the
+    /// vocabulary can be estimated in advance.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<String>::with_capacity(100);
+    /// assert!(hist.capacity() >= 100);
+    /// ```
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        HashMap::with_capacity(capacity).into()
+    }
+
+    /// Collect the counts from `iter` into a new `Histogram`, summing up the counts of `key`s that
+    /// occur multiple times and leaving out counts of zero
     ///
     /// This can be useful if you already counted occurences and just want to analyze it using
     /// `Histogram`.
@@ -139,8 +271,20 @@ impl<K: Hash + Eq> Histogram<K, DefaultHashBuilder> {
     /// ]);
     /// assert_eq!(hist.num_instances(), 10);
     /// ```
+    ///
+    /// ## Duplicate `key`s are summed up
+    /// ```rust
+    /// use histongram::Histogram;
+    ///
+    /// let hist = Histogram::from_counts([("foo", 5), ("bar", 0), ("foo", 2)]);
+    /// assert_eq!(hist.count("foo"), 7);
+    /// assert!(!hist.contains_key("bar"));
+    /// ```
+    ///
+    /// # Panics
+    /// If the sum of the counts of a `key` overflows.
     pub fn from_counts(iter: impl IntoIterator<Item = (K, usize)>) -> Self {
-        HashMap::from_iter(iter).into()
+        iter.into_iter().collect()
     }
 }
 
@@ -148,12 +292,26 @@ impl<K: Hash + Eq, S: BuildHasher> Histogram<K, S> {
     /// Create a new Histogram using the given `hash_builder`
     ///
     /// This allows you to use different hashing algorithms that might fit your use-case better.
+    ///
+    /// To count with another [`Count`] type than `usize` use [`Histogram::default()`] or convert
+    /// an empty [`HashMap`] created with the hasher.
     pub const fn with_hasher(hash_builder: S) -> Self {
         Self {
             map: HashMap::with_hasher(hash_builder),
+            overflow: OverflowPolicy::Panic,
         }
     }
 
+    /// Create a new Histogram with space for at least `capacity` categories using the given
+    /// `hash_builder`
+    ///
+    /// See [`Histogram::with_capacity()`] and [`Histogram::with_hasher()`].
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        HashMap::with_capacity_and_hasher(capacity, hash_builder).into()
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher, C: Count> Histogram<K, S, C> {
     /// Number of categories in the histogram
     ///
     /// # Example
@@ -173,6 +331,13 @@ impl<K: Hash + Eq, S: BuildHasher> Histogram<K, S> {
 
     /// Total number of instances inserted so far
     ///
+    /// Relative frequencies like [`Histogram::count_rel()`] do not use this, so they work even if
+    /// the total does not fit into `C`.
+    ///
+    /// # Panics
+    /// If the total overflows `C` and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`]. With
+    /// [`OverflowPolicy::Saturate`] the total stays at the maximum of `C`.
+    ///
     /// # Example
     /// ```rust
     /// # use histongram::Histogram;
@@ -184,8 +349,169 @@ impl<K: Hash + Eq, S: BuildHasher> Histogram<K, S> {
     /// assert_eq!(hist.num_instances(), 3);
     /// ```
     #[must_use]
-    pub fn num_instances(&self) -> usize {
-        self.map.values().sum()
+    pub fn num_instances(&self) -> C {
+        self.overflow.sum(self.map.values().copied())
+    }
+
+    /// Total number of instances as `f64`, which can not overflow in contrast to
+    /// [`Histogram::num_instances()`]
+    pub(crate) fn instances_f64(&self) -> f64 {
+        self.map.values().map(|cnt| cnt.to_f64()).sum()
+    }
+
+    /// Number of hapax legomena, i.e. `key`s that occurred exactly once
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("aabcd".chars());
+    /// assert_eq!(hist.hapax_count(), 3);
+    /// ```
+    #[must_use]
+    pub fn hapax_count(&self) -> usize {
+        self.hapax_iter().count()
+    }
+
+    /// Iterate over the hapax legomena, i.e. `key`s that occurred exactly once
+    ///
+    /// The order of keys is arbitrary.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("aab".chars());
+    /// assert_eq!(hist.hapax_iter().collect::<Vec<_>>(), vec![&'b']);
+    /// ```
+    pub fn hapax_iter(&self) -> impl Iterator<Item = &K> {
+        self.iter()
+            .filter(|(_key, cnt)| *cnt == C::ONE)
+            .map(|(key, _cnt)| key)
+    }
+
+    /// Ratio of the number of categories (types) to the number of instances (tokens)
+    ///
+    /// This is `1.0` if every `key` occurred only once and approaches `0.0` the more often `key`s
+    /// are repeated. An empty Histogram has a ratio of `0.0`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("aabb".chars());
+    /// assert_eq!(hist.type_token_ratio(), 0.5);
+    /// ```
+    #[must_use]
+    pub fn type_token_ratio(&self) -> f64 {
+        let tokens = self.instances_f64();
+        if tokens == 0.0 {
+            return 0.0;
+        }
+
+        // Rounding is fine when the numbers get to large to fit f64
+        #[allow(clippy::cast_precision_loss)]
+        {
+            self.num_categories() as f64 / tokens
+        }
+    }
+
+    /// Number of categories the histogram can hold without reallocating
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// Reserve space for at least `additional` more categories
+    ///
+    /// # Panics
+    /// If the new allocation size overflows `usize`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let mut hist = Histogram::<String>::new();
+    /// hist.reserve(10);
+    /// assert!(hist.capacity() >= 10);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+    }
+
+    /// Shrink the allocated memory as much as possible, while keeping all categories
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let mut hist = Histogram::<String>::with_capacity(100);
+    /// hist.add_ref("a");
+    ///
+    /// hist.shrink_to_fit();
+    /// assert!(hist.capacity() < 100);
+    /// assert_eq!(hist.count("a"), 1);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit();
+    }
+
+    /// Remove all categories, but keep the allocated memory for reuse
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let mut hist = Histogram::<_>::from_owned_iter("abc".chars());
+    ///
+    /// hist.clear();
+    /// assert!(hist.is_empty());
+    /// assert!(hist.capacity() >= 3);
+    /// ```
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Returns `true` if nothing was added to the histogram yet
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let mut hist = Histogram::new();
+    /// assert!(hist.is_empty());
+    ///
+    /// hist.add_owned("abc");
+    /// assert!(!hist.is_empty());
+    /// ```
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.num_categories() == 0
+    }
+
+    /// Check the invariants of the Histogram in debug builds, does nothing in release builds
+    ///
+    /// Every `key` must have a positive count, and [`Histogram::num_instances()`] must be able to
+    /// sum up all counts. The counting methods always keep this up, but Histograms built from raw
+    /// maps with `From<HashMap>` or by deserializing could break it.
+    ///
+    /// # Panics
+    /// In debug builds, if a count is not positive or the sum of all counts overflows.
+    ///
+    /// # Example
+    /// ```rust
+    /// use hashbrown::HashMap;
+    /// use histongram::Histogram;
+    ///
+    /// let map: HashMap<_, _> = [("a", 2), ("b", 1)].into_iter().collect();
+    /// let hist: Histogram<_> = map.into();
+    /// hist.debug_validate();
+    /// ```
+    pub fn debug_validate(&self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        let mut total = C::ZERO;
+        for (_key, cnt) in self {
+            assert!(cnt > C::ZERO, "count {cnt:?} of a key is not positive");
+            total = total
+                .checked_add(cnt)
+                .unwrap_or_else(|| panic!("sum of all counts overflowed, adding {cnt:?}"));
+        }
     }
 
     /// Add a new occurence of `key` to the Histogram
@@ -215,224 +541,1438 @@ impl<K: Hash + Eq, S: BuildHasher> Histogram<K, S> {
     /// assert_eq!(hist.count("Moin!"), 2);
     /// assert_eq!(hist.count("Hoi!"), 1);
     /// ```
+    ///
+    /// # Panics
+    /// If the count of `key` overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
     pub fn add_ref<'a, Q>(&mut self, val: &'a Q)
     where
         K: Borrow<Q> + From<&'a Q>,
         Q: ?Sized + Hash + Eq,
     {
-        let cnt = self.map.entry_ref(val).or_insert(0);
-        *cnt += 1;
+        let cnt = self.map.entry_ref(val).or_insert(C::ZERO);
+        self.overflow.add(cnt, C::ONE);
     }
 
     /// Add a new occurrence of `key` where ownership of the key moves to the Histogram
     ///
     /// This is useful for types that do not implement [`From<&Self>`] as [`hashbrown`] requires the
     /// use of the [`From<T>`] for its API.
+    ///
+    /// # Panics
+    /// If the count of `key` overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
     pub fn add_owned(&mut self, val: K) {
-        let cnt = self.map.entry(val).or_insert(0);
-        *cnt += 1;
+        let cnt = self.map.entry(val).or_insert(C::ZERO);
+        self.overflow.add(cnt, C::ONE);
     }
 
-    /// Extend this Histogram by counting owned instances of `K`
+    /// Add `n` occurrences of `key` to the Histogram at once
     ///
-    /// This is similar to [`Histogram::extend()`] but taking owned instances instead of references.
-    /// It is useful for types that are cheap to clone, such as types that implement [`Copy`].
-    pub fn extend_from_owned<I: IntoIterator<Item = K>>(&mut self, iter: I) {
-        for item in iter {
-            self.add_owned(item);
-        }
-    }
-
-    /// Add all the occurrences from `other` to self
-    pub fn append(&mut self, other: Self) {
-        for (key, cnt) in other {
-            let old = self.map.entry(key).or_default();
-            *old += cnt;
-        }
-    }
-
-    /// Get the number of times `key` was added to this histogram
+    /// This is the same as calling [`Histogram::add_ref()`] `n` times. It is useful to merge counts
+    /// that were already aggregated somewhere else. Adding zero, negative or NaN occurrences does
+    /// nothing.
     ///
-    /// Returns `0` for absent `key`s.
+    /// # Panics
+    /// If the count of `key` overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
     ///
     /// # Example
     /// ```rust
-    /// # use histongram::Histogram;
+    /// use histongram::Histogram;
     /// let mut hist = Histogram::<String>::new();
     ///
-    /// hist.add_ref("present");
-    /// assert_eq!(hist.count("present"), 1);
-    /// assert_eq!(hist.count("absent"), 0);
+    /// hist.add_ref_n("a", 5);
+    /// hist.add_ref_n("a", 2);
+    /// hist.add_ref_n("b", 0);
+    ///
+    /// assert_eq!(hist.count("a"), 7);
+    /// assert!(!hist.contains_key("b"));
     /// ```
-    pub fn count<Q>(&self, key: &Q) -> usize
+    pub fn add_ref_n<'a, Q>(&mut self, val: &'a Q, n: C)
     where
+        K: Borrow<Q> + From<&'a Q>,
         Q: ?Sized + Hash + Eq,
-        K: Borrow<Q>,
     {
-        self.map.get(key).copied().unwrap_or(0)
+        // Only categories that occurred at least once are kept, this also skips NaN
+        if n.partial_cmp(&C::ZERO) != Some(Ordering::Greater) {
+            return;
+        }
+        let cnt = self.map.entry_ref(val).or_insert(C::ZERO);
+        self.overflow.add(cnt, n);
     }
 
-    /// Get the relative number of times `key` was added to this histogram
+    /// Add `n` occurrences of `key` at once, where ownership of the key moves to the Histogram
     ///
-    /// Returns `0.0` for absent `key`s, so also if asked for any key in an empty `Histogram`.
-    /// If all occurrences so far matched `key` `count_rel` will return `1.0`.
+    /// This is the same as calling [`Histogram::add_owned()`] `n` times. Adding zero, negative or
+    /// NaN occurrences does nothing.
+    ///
+    /// # Panics
+    /// If the count of `key` overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    pub fn add_owned_n(&mut self, val: K, n: C) {
+        // Only categories that occurred at least once are kept, this also skips NaN
+        if n.partial_cmp(&C::ZERO) != Some(Ordering::Greater) {
+            return;
+        }
+        let cnt = self.map.entry(val).or_insert(C::ZERO);
+        self.overflow.add(cnt, n);
+    }
+
+    /// Add a new occurence of `key` to the Histogram, failing if its count would overflow
+    ///
+    /// This is the same as [`Histogram::add_ref()`], but ignores the [`OverflowPolicy`]. The
+    /// Histogram is left unchanged if an error is returned.
+    ///
+    /// # Errors
+    /// If the count of `key` does not fit into `C` anymore.
     ///
     /// # Example
     /// ```rust
-    /// # use histongram::Histogram;
-    /// let mut hist = Histogram::<String>::new();
+    /// use histongram::{CountOverflow, DefaultHashBuilder, Histogram};
+    /// let mut hist = Histogram::<String, DefaultHashBuilder, u8>::default();
     ///
-    /// hist.add_ref("present");
-    /// assert_eq!(hist.count_rel("present"), 1.0);
-    /// assert_eq!(hist.count_rel("absent"), 0.0);
+    /// for _ in 0..255 {
+    ///     hist.try_add_ref("a").unwrap();
+    /// }
+    /// assert_eq!(hist.try_add_ref("a"), Err(CountOverflow));
+    /// assert_eq!(hist.count("a"), 255);
     /// ```
-    pub fn count_rel<Q>(&self, key: &Q) -> f64
+    pub fn try_add_ref<'a, Q>(&mut self, val: &'a Q) -> Result<(), CountOverflow>
     where
+        K: Borrow<Q> + From<&'a Q>,
         Q: ?Sized + Hash + Eq,
-        K: Borrow<Q>,
     {
-        let total = self.num_instances();
-        if total == 0 {
-            // There are no instances, so `key` can also not be in the list
-            // And 0% seem reasonable for an absent key
-            return 0.0;
-        }
+        // NOTE: A new entry will always fit a count of one, so nothing is inserted on errors
+        let cnt = self.map.entry_ref(val).or_insert(C::ZERO);
+        *cnt = cnt.checked_add(C::ONE).ok_or(CountOverflow)?;
+        Ok(())
+    }
 
-        // Rounding is fine when the numbers get to large to fit f64
-        #[allow(clippy::cast_precision_loss)]
-        {
-            self.count(key) as f64 / total as f64
-        }
+    /// Add a new occurrence of `key` where ownership of the key moves to the Histogram, failing if
+    /// its count would overflow
+    ///
+    /// This is the same as [`Histogram::add_owned()`], but ignores the [`OverflowPolicy`]. The
+    /// Histogram is left unchanged if an error is returned.
+    ///
+    /// # Errors
+    /// If the count of `key` does not fit into `C` anymore.
+    pub fn try_add_owned(&mut self, val: K) -> Result<(), CountOverflow> {
+        // NOTE: A new entry will always fit a count of one, so nothing is inserted on errors
+        let cnt = self.map.entry(val).or_insert(C::ZERO);
+        *cnt = cnt.checked_add(C::ONE).ok_or(CountOverflow)?;
+        Ok(())
     }
 
-    /// Iterate over all `key`s and their counts in `self` that have occurred at least once.
+    /// The [`OverflowPolicy`] used when counts get too large for `C`
+    #[must_use]
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow
+    }
+
+    /// Set the [`OverflowPolicy`] used when counts get too large for `C`
     ///
-    /// The order of keys is arbitrary.
-    pub fn iter(&self) -> impl Iterator<Item = (&K, usize)> {
-        self.into_iter()
+    /// The default is [`OverflowPolicy::Panic`].
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.overflow = policy;
     }
 
-    /// Iterate over all `key`s which have occurred at least once, together with its relative
-    /// number of occurrences.
+    /// Extend this Histogram by counting owned instances of `K`
     ///
-    /// The order of keys is arbitrary.
-    pub fn iter_rel(&self) -> impl Iterator<Item = (&K, f64)> {
-        // If the counts get to big rounding is fine here.
-        #[allow(clippy::cast_precision_loss)]
-        {
-            let total = self.num_instances() as f64;
-            self.iter().map(move |(k, cnt)| (k, cnt as f64 / total))
+    /// This is similar to [`Histogram::extend()`] but taking owned instances instead of references.
+    /// It is useful for types that are cheap to clone, such as types that implement [`Copy`].
+    /// Generic code using [`Extend`] can pass `(key, C::ONE)` pairs instead, as there is no
+    /// `Extend<K>` implementation.
+    pub fn extend_from_owned<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for item in iter {
+            self.add_owned(item);
         }
     }
 
-    /// Get a vector of `key`s and `count`s sorted descending by `count`.
+    /// Extend this Histogram by adding pre-aggregated `(key, count)` pairs
     ///
-    /// This consumes the Histogram to avoid cloning the `key`s.
+    /// This is the same as calling [`Histogram::add_owned_n()`] for every pair, so counts of `key`s
+    /// that occur multiple times are summed up. It is also available via [`Extend`] and
+    /// [`FromIterator`] for `(K, C)` pairs.
     ///
+    /// # Panics
+    /// If a count overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    ///
+    /// # Example
     /// ```rust
     /// use histongram::Histogram;
-    /// let hist: Histogram<_> = Histogram::from_owned_iter("aaaxxzzzzz".chars());
+    /// let mut hist = Histogram::<&str>::new();
     ///
-    /// assert_eq!(hist.sorted_occurrences(), vec![
-    ///     ('z', 5),
-    ///     ('a', 3),
-    ///     ('x', 2),
-    /// ]);
+    /// hist.extend_from_counts([("a", 5), ("b", 2), ("a", 1)]);
+    /// assert_eq!(hist.count(&"a"), 6);
+    ///
+    /// let other: Histogram<&str> = [("a", 1), ("c", 3)].into_iter().collect();
+    /// assert_eq!(other.count(&"c"), 3);
     /// ```
-    #[must_use]
-    pub fn sorted_occurrences(self) -> Vec<(K, usize)> {
-        let mut counts: Vec<_> = self.into_iter().collect();
-        // NOTE: unstable is okay here, as the map order is already arbitrary
-        counts.sort_unstable_by_key(|(_key, cnt)| Reverse(*cnt));
-        counts
+    pub fn extend_from_counts<I: IntoIterator<Item = (K, C)>>(&mut self, iter: I) {
+        for (key, n) in iter {
+            self.add_owned_n(key, n);
+        }
     }
 
-    /// Turn this histogram into a [`HashMap`](`std::collections::HashMap`) from `std`
+    /// Add all the occurrences from `other` to self
     ///
-    /// This can be useful if you do not want to use another `HashMap` such as
-    /// [`hashbrown::HashMap`] in your code base. Or you already have a `struct` containing
-    /// `std::collections::HashMap`s.
+    /// # Panics
+    /// If a count overflows and the [`OverflowPolicy`] of `self` is [`OverflowPolicy::Panic`].
+    pub fn append(&mut self, other: Self) {
+        for (key, cnt) in other {
+            let old = self.map.entry(key).or_insert(C::ZERO);
+            self.overflow.add(old, cnt);
+        }
+    }
+
+    /// Add all the occurrences from `other` to self without consuming `other`
+    ///
+    /// In contrast to [`Histogram::append()`] the `key`s of `other` are only cloned if they are
+    /// not yet present in `self`. `other` can use a different hasher than `self`.
+    ///
+    /// This is also available as `hist += &other`.
     ///
     /// # Example
     /// ```rust
-    /// use std::collections::HashMap;
-    /// use histongram::Histogram;
-    ///
-    /// struct MyStruct {
-    ///     counts: HashMap<String, usize>,
-    /// }
+    /// # use histongram::Histogram;
+    /// let mut hist = Histogram::<String>::from_iter(["a", "b"]);
+    /// let other = Histogram::<String>::from_iter(["a", "c"]);
     ///
-    /// let mut hist = Histogram::new();
-    /// hist.extend(["foo", "bar", "baz"]);
+    /// hist.add_counts_from(&other);
+    /// hist += &other;
     ///
-    /// let ms = MyStruct { counts: hist.into_std_hash_map() };
-    /// assert_eq!(ms.counts["foo"], 1);
+    /// assert_eq!(hist.count("a"), 3);
+    /// assert_eq!(hist.count("b"), 1);
+    /// assert_eq!(hist.count("c"), 2);
     /// ```
-    pub fn into_std_hash_map(self) -> std::collections::HashMap<K, usize> {
-        self.map.into_iter().collect()
-    }
-}
-
-impl<K: Hash + Eq, S: BuildHasher + Default> Histogram<K, S> {
-    /// Create a new Histogram by counting owned instanes of `K` in `iter`.
     ///
-    /// This is similar to [`Histogram::from_iter()`] but taking owned values instead of references.
-    ///
-    /// See also [`Histogram::extend_from_owned()`] for motivations.
-    pub fn from_owned_iter<I>(iter: I) -> Self
+    /// # Panics
+    /// If a count overflows and the [`OverflowPolicy`] of `self` is [`OverflowPolicy::Panic`].
+    pub fn add_counts_from<S2: BuildHasher>(&mut self, other: &Histogram<K, S2, C>)
     where
-        I: IntoIterator<Item = K>,
+        K: Clone,
     {
-        let mut h = Self::default();
-        h.extend_from_owned(iter);
-        h
-    }
-}
-
-// This can not be derived as it would then only be available if `K: Default` which we don't need here.
-impl<K: Hash + Eq, S: BuildHasher + Default> Default for Histogram<K, S> {
-    fn default() -> Self {
-        Self {
-            map: HashMap::default(),
+        for (key, cnt) in other {
+            if let Some(old) = self.map.get_mut(key) {
+                self.overflow.add(old, cnt);
+            } else {
+                self.map.insert(key.clone(), cnt);
+            }
         }
     }
-}
 
-impl<'a, K, S, Q> Extend<&'a Q> for Histogram<K, S>
-where
-    K: Hash + Eq + Borrow<Q> + From<&'a Q>,
-    Q: ?Sized + Hash + Eq + 'a,
-    S: BuildHasher,
-{
-    fn extend<T: IntoIterator<Item = &'a Q>>(&mut self, iter: T) {
-        for item in iter {
+    /// Remove all the occurrences in `other` from self
+    ///
+    /// Counts saturate at zero, so `key`s that occurred more often in `other` than in `self` are
+    /// removed. Categories whose count drops to zero are always removed, as a Histogram only
+    /// contains `key`s that occurred at least once. `other` can use a different hasher than `self`.
+    ///
+    /// This is also available as `hist -= &other`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let mut this_week = Histogram::<String>::from_iter(["a", "a", "b", "new"]);
+    /// let last_week = Histogram::<String>::from_iter(["a", "b", "b", "old"]);
+    ///
+    /// this_week.subtract(&last_week);
+    ///
+    /// assert_eq!(this_week.count("a"), 1);
+    /// assert_eq!(this_week.count("new"), 1);
+    /// assert!(!this_week.contains_key("b"));
+    /// assert!(!this_week.contains_key("old"));
+    /// ```
+    pub fn subtract<S2: BuildHasher>(&mut self, other: &Histogram<K, S2, C>) {
+        for (key, cnt) in other {
+            if let Some(old) = self.map.get_mut(key) {
+                *old = old.saturating_sub(cnt);
+                if *old <= C::ZERO {
+                    self.map.remove(key);
+                }
+            }
+        }
+    }
+
+    /// Remove one occurrence of `key` from the Histogram
+    ///
+    /// Returns the number of occurrences of `key` that are left. If this was the last occurrence,
+    /// the category is removed entirely. Decrementing an absent `key` does nothing and returns `0`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let mut hist: Histogram<String> = ["a", "a", "b"].into_iter().collect();
+    ///
+    /// assert_eq!(hist.decrement("a"), 1);
+    /// assert_eq!(hist.decrement("b"), 0);
+    /// assert_eq!(hist.decrement("c"), 0);
+    ///
+    /// assert_eq!(hist.num_categories(), 1);
+    /// assert_eq!(hist.num_instances(), 1);
+    /// ```
+    pub fn decrement<Q>(&mut self, key: &Q) -> C
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        let Some(cnt) = self.map.get_mut(key) else {
+            return C::ZERO;
+        };
+
+        *cnt = cnt.saturating_sub(C::ONE);
+        let left = *cnt;
+        if left <= C::ZERO {
+            // Only categories that occurred at least once are kept
+            self.map.remove(key);
+        }
+        left
+    }
+
+    /// Remove the category `key` from the Histogram
+    ///
+    /// Returns the number of occurrences `key` had before, which is `0` for absent `key`s.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let mut hist: Histogram<String> = ["a", "a", "b"].into_iter().collect();
+    ///
+    /// assert_eq!(hist.remove("a"), 2);
+    /// assert_eq!(hist.remove("a"), 0);
+    ///
+    /// assert_eq!(hist.num_categories(), 1);
+    /// assert_eq!(hist.num_instances(), 1);
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> C
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        self.map.remove(key).unwrap_or(C::ZERO)
+    }
+
+    /// Only keep the categories for which `keep` returns `true`
+    ///
+    /// `keep` gets passed each `key` together with its count.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let mut hist = Histogram::<_>::from_owned_iter("aaabbc".chars());
+    ///
+    /// hist.retain(|&key, _cnt| key != 'a');
+    /// assert_eq!(hist.num_instances(), 3);
+    ///
+    /// hist.retain(|_key, cnt| cnt >= 2);
+    /// assert_eq!(hist.num_instances(), 2);
+    /// assert_eq!(hist.sorted_occurrences(), vec![('b', 2)]);
+    /// ```
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&K, C) -> bool,
+    {
+        self.map.retain(|key, cnt| keep(key, *cnt));
+    }
+
+    /// Split the Histogram into the categories for which `pred` returns `true` and the rest
+    ///
+    /// `pred` gets passed each `key` together with its count. Both Histograms keep the hasher and
+    /// the [`OverflowPolicy`] of `self`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let words: Histogram<&str> = "the cat and the dog".split(' ').collect();
+    ///
+    /// let (stopwords, content) = words.partition(|key, _cnt| ["the", "and"].contains(key));
+    /// assert_eq!(stopwords.num_instances(), 3);
+    /// assert_eq!(content.num_categories(), 2);
+    /// ```
+    #[must_use]
+    pub fn partition<F>(self, mut pred: F) -> (Self, Self)
+    where
+        S: Clone,
+        F: FnMut(&K, C) -> bool,
+    {
+        let mut matching = HashMap::with_hasher(self.map.hasher().clone());
+        let mut rest = HashMap::with_hasher(self.map.hasher().clone());
+        for (key, cnt) in self.map {
+            if pred(&key, cnt) {
+                matching.insert(key, cnt);
+            } else {
+                rest.insert(key, cnt);
+            }
+        }
+
+        (
+            Self {
+                map: matching,
+                overflow: self.overflow,
+            },
+            Self {
+                map: rest,
+                overflow: self.overflow,
+            },
+        )
+    }
+
+    /// Remove all `key`s and their counts from the Histogram and iterate over them
+    ///
+    /// The Histogram is empty afterwards, but keeps its allocated memory so it can be reused
+    /// without growing again. The order of `key`s is arbitrary.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let mut hist = Histogram::<_>::from_owned_iter("aab".chars());
+    ///
+    /// let mut drained: Vec<_> = hist.drain().collect();
+    /// drained.sort_unstable();
+    /// assert_eq!(drained, vec![('a', 2), ('b', 1)]);
+    /// assert!(hist.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> hash_map::Drain<'_, K, C> {
+        self.map.drain()
+    }
+
+    /// Remove all categories that occurred less than `min_count` times
+    ///
+    /// This is useful to get rid of noise, such as words that only occurred once.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let mut hist = Histogram::<_>::from_owned_iter("aaabbc".chars());
+    ///
+    /// hist.prune_below(2);
+    /// assert_eq!(hist.num_categories(), 2);
+    /// assert!(!hist.contains_key(&'c'));
+    /// ```
+    pub fn prune_below(&mut self, min_count: C) {
+        self.retain(|_key, cnt| cnt >= min_count);
+    }
+
+    /// Get the number of times `key` was added to this histogram
+    ///
+    /// Returns `0` for absent `key`s.
+    ///
+    /// Like [`HashMap::get()`](std::collections::HashMap::get) the `key` may be any borrowed form
+    /// of the `key` type, so a `Histogram<String>` can be queried with a `&str` without
+    /// allocating.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let mut hist = Histogram::<String>::new();
+    ///
+    /// hist.add_ref("present");
+    /// assert_eq!(hist.count("present"), 1);
+    /// assert_eq!(hist.count("absent"), 0);
+    /// ```
+    pub fn count<Q>(&self, key: &Q) -> C
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        self.map.get(key).copied().unwrap_or(C::ZERO)
+    }
+
+    /// Check if `key` was added to this histogram at least once
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let mut hist = Histogram::<String>::new();
+    ///
+    /// hist.add_ref("present");
+    /// assert!(hist.contains_key("present"));
+    /// assert!(!hist.contains_key("absent"));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Check if `key` was added to this histogram at least once
+    ///
+    /// This is the same as [`Histogram::contains_key()`], named like `contains` on the
+    /// collections `key`s are usually counted from.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("abc".chars());
+    /// assert!(hist.contains(&'a'));
+    /// assert!(!hist.contains(&'z'));
+    /// ```
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        self.contains_key(key)
+    }
+
+    /// Iterate over all `key`s that have occurred at least once, i.e. the vocabulary
+    ///
+    /// The order of keys is arbitrary.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("abcab".chars());
+    ///
+    /// let mut keys: Vec<_> = hist.keys().copied().collect();
+    /// keys.sort_unstable();
+    /// assert_eq!(keys, vec!['a', 'b', 'c']);
+    /// ```
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.map.keys()
+    }
+
+    /// Iterate over the counts of all `key`s that have occurred at least once
+    ///
+    /// The order is arbitrary, but matches [`Histogram::keys()`] as long as `self` is not
+    /// modified in between.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("abcab".chars());
+    ///
+    /// let mut counts: Vec<_> = hist.counts().collect();
+    /// counts.sort_unstable();
+    /// assert_eq!(counts, vec![1, 2, 2]);
+    /// ```
+    pub fn counts(&self) -> impl Iterator<Item = C> + '_ {
+        self.map.values().copied()
+    }
+
+    /// Get the relative number of times `key` was added to this histogram
+    ///
+    /// Returns `0.0` for absent `key`s, so also if asked for any key in an empty `Histogram`.
+    /// If all occurrences so far matched `key` `count_rel` will return `1.0`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let mut hist = Histogram::<String>::new();
+    ///
+    /// hist.add_ref("present");
+    /// assert_eq!(hist.count_rel("present"), 1.0);
+    /// assert_eq!(hist.count_rel("absent"), 0.0);
+    /// ```
+    pub fn count_rel<Q>(&self, key: &Q) -> f64
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        let total = self.instances_f64();
+        if total == 0.0 {
+            // There are no instances, so `key` can also not be in the list
+            // And 0% seem reasonable for an absent key
+            return 0.0;
+        }
+
+        self.count(key).to_f64() / total
+    }
+
+    /// Iterate over all `key`s and their counts in `self` that have occurred at least once.
+    ///
+    /// The order of keys is arbitrary.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, C)> {
+        self.into_iter()
+    }
+
+    /// Iterate over all `key`s which have occurred at least once, together with its relative
+    /// number of occurrences.
+    ///
+    /// The order of keys is arbitrary.
+    pub fn iter_rel(&self) -> impl Iterator<Item = (&K, f64)> {
+        let total = self.instances_f64();
+        self.iter().map(move |(k, cnt)| (k, cnt.to_f64() / total))
+    }
+
+    /// Shannon entropy of the relative frequencies in bits
+    ///
+    /// An empty Histogram or one with a single category has an entropy of `0.0`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("aabbccdd".chars());
+    /// assert_eq!(hist.entropy(), 2.0);
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn entropy(&self) -> f64 {
+        self.entropy_nats() / core::f64::consts::LN_2
+    }
+
+    /// Shannon entropy of the relative frequencies in nats, so using the natural logarithm
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("ab".chars());
+    /// assert_eq!(hist.entropy_nats(), core::f64::consts::LN_2);
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn entropy_nats(&self) -> f64 {
+        // Every category occurred at least once, so `p > 0` and `ln(p)` is finite
+        self.iter_rel().fold(0.0, |h, (_key, p)| h - p * p.ln())
+    }
+
+    /// Perplexity of the relative frequencies, which is `2` to the power of [`Histogram::entropy()`]
+    ///
+    /// This can be seen as the effective number of equally likely categories.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("aabbccdd".chars());
+    /// assert_eq!(hist.perplexity(), 4.0);
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn perplexity(&self) -> f64 {
+        self.entropy_nats().exp()
+    }
+
+    /// Get a vector of `key`s and `count`s sorted descending by `count`.
+    ///
+    /// This consumes the Histogram to avoid cloning the `key`s.
+    ///
+    /// ```rust
+    /// use histongram::Histogram;
+    /// let hist: Histogram<_> = Histogram::from_owned_iter("aaaxxzzzzz".chars());
+    ///
+    /// assert_eq!(hist.sorted_occurrences(), vec![
+    ///     ('z', 5),
+    ///     ('a', 3),
+    ///     ('x', 2),
+    /// ]);
+    /// ```
+    #[must_use]
+    pub fn sorted_occurrences(self) -> Vec<(K, C)> {
+        self.sorted_occurrences_by(|(_, a), (_, b)| b.total_cmp(a))
+    }
+
+    /// Get a vector of `key`s and `count`s sorted in a deterministic [`SortOrder`].
+    ///
+    /// In contrast to [`Histogram::sorted_occurrences()`] ties are broken by sorting by `key`, so
+    /// the result does not depend on the arbitrary order inside the Histogram. This is useful for
+    /// reports and snapshot tests.
+    ///
+    /// ```rust
+    /// use histongram::{Histogram, SortOrder};
+    /// let hist: Histogram<_> = Histogram::from_owned_iter("aaxxzzzzzb".chars());
+    ///
+    /// assert_eq!(hist.clone().sorted_occurrences_in(SortOrder::CountDescending), vec![
+    ///     ('z', 5),
+    ///     ('a', 2),
+    ///     ('x', 2),
+    ///     ('b', 1),
+    /// ]);
+    /// assert_eq!(hist.sorted_occurrences_in(SortOrder::KeyAscending), vec![
+    ///     ('a', 2),
+    ///     ('b', 1),
+    ///     ('x', 2),
+    ///     ('z', 5),
+    /// ]);
+    /// ```
+    #[must_use]
+    pub fn sorted_occurrences_in(self, order: SortOrder) -> Vec<(K, C)>
+    where
+        K: Ord,
+    {
+        self.sorted_occurrences_by(|(ka, a), (kb, b)| order.compare((ka, a), (kb, b)))
+    }
+
+    /// Get a vector of `key`s and `count`s sorted with the comparator function `compare`.
+    ///
+    /// This consumes the Histogram to avoid cloning the `key`s. Elements that `compare` considers
+    /// equal are in arbitrary order.
+    ///
+    /// ```rust
+    /// use histongram::Histogram;
+    /// let hist: Histogram<String> = ["bb", "a", "ccc", "a"].into_iter().collect();
+    ///
+    /// let by_length = hist.sorted_occurrences_by(|(a, _), (b, _)| a.len().cmp(&b.len()));
+    /// assert_eq!(by_length, vec![
+    ///     ("a".to_string(), 2),
+    ///     ("bb".to_string(), 1),
+    ///     ("ccc".to_string(), 1),
+    /// ]);
+    /// ```
+    #[must_use]
+    pub fn sorted_occurrences_by<F>(self, compare: F) -> Vec<(K, C)>
+    where
+        F: FnMut(&(K, C), &(K, C)) -> Ordering,
+    {
+        let mut counts: Vec<_> = self.into_iter().collect();
+        // NOTE: unstable is okay here, as the map order is already arbitrary
+        counts.sort_unstable_by(compare);
+        counts
+    }
+
+    /// Iterate the `key`s and `count`s in [`SortOrder::CountDescending`], consuming the Histogram.
+    ///
+    /// In contrast to [`Histogram::sorted_occurrences_in()`] the sorting happens lazily: building
+    /// the iterator takes `O(n)` and every step `O(log n)`. So taking only the first few elements
+    /// is much cheaper than sorting everything.
+    ///
+    /// ```rust
+    /// use histongram::Histogram;
+    /// let hist: Histogram<_> = Histogram::from_owned_iter("aaxxzzzzzb".chars());
+    ///
+    /// let mut sorted = hist.into_sorted_iter();
+    /// assert_eq!(sorted.next(), Some(('z', 5)));
+    /// assert_eq!(sorted.next(), Some(('a', 2)));
+    /// assert_eq!(sorted.next(), Some(('x', 2)));
+    /// ```
+    pub fn into_sorted_iter(self) -> impl Iterator<Item = (K, C)>
+    where
+        K: Ord,
+    {
+        let mut heap: BinaryHeap<_> = self
+            .into_iter()
+            .map(|(key, cnt)| CountDescending(key, cnt))
+            .collect();
+        iter::from_fn(move || heap.pop().map(|CountDescending(key, cnt)| (key, cnt)))
+    }
+
+    /// Iterate the `key`s and `count`s in [`SortOrder::CountDescending`] without consuming the
+    /// Histogram.
+    ///
+    /// This is the borrowing counterpart to [`Histogram::into_sorted_iter()`].
+    ///
+    /// ```rust
+    /// use histongram::Histogram;
+    /// let hist: Histogram<_> = Histogram::from_owned_iter("aaxxzzzzzb".chars());
+    ///
+    /// let top: Vec<_> = hist.sorted_iter().take(2).collect();
+    /// assert_eq!(top, vec![(&'z', 5), (&'a', 2)]);
+    /// ```
+    pub fn sorted_iter(&self) -> impl Iterator<Item = (&K, C)>
+    where
+        K: Ord,
+    {
+        let mut heap: BinaryHeap<_> = self
+            .iter()
+            .map(|(key, cnt)| CountDescending(key, cnt))
+            .collect();
+        iter::from_fn(move || heap.pop().map(|CountDescending(key, cnt)| (key, cnt)))
+    }
+
+    /// Get a vector of `key`s and their relative number of occurrences sorted descending.
+    ///
+    /// This is the relative counterpart to [`Histogram::sorted_occurrences()`], but borrows the
+    /// `key`s instead of consuming the Histogram. An empty Histogram gives an empty vector.
+    ///
+    /// ```rust
+    /// use histongram::Histogram;
+    /// let hist: Histogram<_> = Histogram::from_owned_iter("aaaxxzzzzz".chars());
+    ///
+    /// assert_eq!(hist.sorted_occurrences_rel(), vec![
+    ///     (&'z', 0.5),
+    ///     (&'a', 0.3),
+    ///     (&'x', 0.2),
+    /// ]);
+    /// ```
+    #[must_use]
+    pub fn sorted_occurrences_rel(&self) -> Vec<(&K, f64)> {
+        let mut counts: Vec<_> = self.iter().collect();
+        // NOTE: unstable is okay here, as the map order is already arbitrary
+        counts.sort_unstable_by(|(_, a), (_, b)| b.total_cmp(a));
+
+        let total = self.instances_f64();
+        counts
+            .into_iter()
+            .map(|(key, cnt)| (key, cnt.to_f64() / total))
+            .collect()
+    }
+
+    /// Iterate the `key`s in descending order of count, together with the running total of
+    /// counts and the fraction of all instances covered so far.
+    ///
+    /// The order of `key`s with equal counts is arbitrary. The last fraction is `1.0` up to
+    /// rounding.
+    ///
+    /// ```rust
+    /// use histongram::Histogram;
+    /// let hist: Histogram<_> = Histogram::from_owned_iter("aaaxxzzzzz".chars());
+    ///
+    /// assert_eq!(hist.iter_cumulative().collect::<Vec<_>>(), vec![
+    ///     (&'z', 5, 0.5),
+    ///     (&'a', 8, 0.8),
+    ///     (&'x', 10, 1.0),
+    /// ]);
+    /// ```
+    pub fn iter_cumulative(&self) -> impl Iterator<Item = (&K, C, f64)> {
+        let mut counts: Vec<_> = self.iter().collect();
+        // NOTE: unstable is okay here, as the map order is already arbitrary
+        counts.sort_unstable_by(|(_, a), (_, b)| b.total_cmp(a));
+
+        let total = self.instances_f64();
+        let overflow = self.overflow;
+        counts.into_iter().scan(C::ZERO, move |seen, (key, cnt)| {
+            overflow.add(seen, cnt);
+            Some((key, *seen, seen.to_f64() / total))
+        })
+    }
+
+    /// Number of most common categories needed to cover at least the fraction `p` of all
+    /// instances
+    ///
+    /// A `p` of `0.0` or less needs no categories, a `p` above `1.0` needs all of them.
+    ///
+    /// ```rust
+    /// use histongram::Histogram;
+    /// let hist: Histogram<_> = Histogram::from_owned_iter("aaaxxzzzzz".chars());
+    ///
+    /// assert_eq!(hist.coverage(0.5), 1);
+    /// assert_eq!(hist.coverage(0.75), 2);
+    /// assert_eq!(hist.coverage(0.9), 3);
+    /// ```
+    #[must_use]
+    pub fn coverage(&self, p: f64) -> usize {
+        if p <= 0.0 {
+            return 0;
+        }
+
+        self.iter_cumulative()
+            .position(|(_key, _cnt, fraction)| fraction >= p)
+            .map_or(self.num_categories(), |idx| idx + 1)
+    }
+
+    /// Get the most common `key` and its count, or `None` for an empty Histogram
+    ///
+    /// If multiple `key`s share the highest count, an arbitrary one of them is returned. See
+    /// [`Histogram::most_common_ordered()`] for a deterministic choice.
+    ///
+    /// ```rust
+    /// use histongram::Histogram;
+    /// let hist: Histogram<_> = Histogram::from_owned_iter("aaaxxzzzzz".chars());
+    ///
+    /// assert_eq!(hist.most_common(), Some((&'z', 5)));
+    /// assert_eq!(hist.least_common(), Some((&'x', 2)));
+    /// ```
+    #[must_use]
+    pub fn most_common(&self) -> Option<(&K, C)> {
+        self.iter().max_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+
+    /// Get the least common `key` and its count, or `None` for an empty Histogram
+    ///
+    /// If multiple `key`s share the lowest count, an arbitrary one of them is returned. See
+    /// [`Histogram::least_common_ordered()`] for a deterministic choice.
+    #[must_use]
+    pub fn least_common(&self) -> Option<(&K, C)> {
+        self.iter().min_by(|(_, a), (_, b)| a.total_cmp(b))
+    }
+
+    /// Get the most common `key` and its count, choosing the smallest `key` on ties
+    ///
+    /// ```rust
+    /// use histongram::Histogram;
+    /// let hist: Histogram<_> = Histogram::from_owned_iter("zzaabbc".chars());
+    ///
+    /// assert_eq!(hist.most_common_ordered(), Some((&'a', 2)));
+    /// ```
+    #[must_use]
+    pub fn most_common_ordered(&self) -> Option<(&K, C)>
+    where
+        K: Ord,
+    {
+        self.iter()
+            .max_by(|(ka, a), (kb, b)| a.total_cmp(b).then(kb.cmp(ka)))
+    }
+
+    /// Get the least common `key` and its count, choosing the smallest `key` on ties
+    ///
+    /// ```rust
+    /// use histongram::Histogram;
+    /// let hist: Histogram<_> = Histogram::from_owned_iter("zaabbcc".chars());
+    ///
+    /// assert_eq!(hist.least_common_ordered(), Some((&'z', 1)));
+    /// ```
+    #[must_use]
+    pub fn least_common_ordered(&self) -> Option<(&K, C)>
+    where
+        K: Ord,
+    {
+        self.iter()
+            .min_by(|(ka, a), (kb, b)| a.total_cmp(b).then(ka.cmp(kb)))
+    }
+
+    /// The highest count of any `key`, or `None` for an empty Histogram
+    ///
+    /// ```rust
+    /// use histongram::Histogram;
+    /// let hist: Histogram<_> = Histogram::from_owned_iter("aaaxxzzzzz".chars());
+    ///
+    /// assert_eq!(hist.max_count(), Some(5));
+    /// assert_eq!(hist.min_count(), Some(2));
+    /// ```
+    #[must_use]
+    pub fn max_count(&self) -> Option<C> {
+        self.most_common().map(|(_key, cnt)| cnt)
+    }
+
+    /// The lowest count of any `key`, or `None` for an empty Histogram
+    #[must_use]
+    pub fn min_count(&self) -> Option<C> {
+        self.least_common().map(|(_key, cnt)| cnt)
+    }
+
+    /// Get the `k` most common `key`s and their counts sorted descending by count.
+    ///
+    /// In contrast to [`Histogram::sorted_occurrences()`] this does not sort all categories, but
+    /// keeps only the best `k` categories around, so it runs in `O(n log k)`. The order of `key`s
+    /// with the same count is arbitrary.
+    ///
+    /// ```rust
+    /// use histongram::Histogram;
+    /// let hist: Histogram<_> = Histogram::from_owned_iter("aaaxxzzzzz".chars());
+    ///
+    /// assert_eq!(hist.top_k(2), vec![(&'z', 5), (&'a', 3)]);
+    /// ```
+    #[must_use]
+    pub fn top_k(&self, k: usize) -> Vec<(&K, C)> {
+        // There are never more than `num_categories()` to return, even for huge `k`s
+        let k = k.min(self.num_categories());
+        if k == 0 {
+            return Vec::new();
+        }
+
+        // Min-heap of the best `k` seen so far, so the worst of them can be evicted quickly
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        for (key, cnt) in self {
+            heap.push(Reverse(ByCount(cnt, key)));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(ByCount(cnt, key))| (key, cnt))
+            .collect()
+    }
+
+    /// Get the `k` least common `key`s and their counts sorted ascending by count.
+    ///
+    /// This is the counterpart to [`Histogram::top_k()`] and also runs in `O(n log k)`.
+    ///
+    /// ```rust
+    /// use histongram::Histogram;
+    /// let hist: Histogram<_> = Histogram::from_owned_iter("aaaxxzzzzz".chars());
+    ///
+    /// assert_eq!(hist.bottom_k(2), vec![(&'x', 2), (&'a', 3)]);
+    /// ```
+    #[must_use]
+    pub fn bottom_k(&self, k: usize) -> Vec<(&K, C)> {
+        // There are never more than `num_categories()` to return, even for huge `k`s
+        let k = k.min(self.num_categories());
+        if k == 0 {
+            return Vec::new();
+        }
+
+        // Max-heap of the worst `k` seen so far, so the best of them can be evicted quickly
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        for (key, cnt) in self {
+            heap.push(ByCount(cnt, key));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|ByCount(cnt, key)| (key, cnt))
+            .collect()
+    }
+
+    /// Turn this histogram into a [`HashMap`](`std::collections::HashMap`) from `std`
+    ///
+    /// This can be useful if you do not want to use another `HashMap` such as
+    /// [`hashbrown::HashMap`] in your code base. Or you already have a `struct` containing
+    /// `std::collections::HashMap`s.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use histongram::Histogram;
+    ///
+    /// struct MyStruct {
+    ///     counts: HashMap<String, usize>,
+    /// }
+    ///
+    /// let mut hist = Histogram::new();
+    /// hist.extend(["foo", "bar", "baz"]);
+    ///
+    /// let ms = MyStruct { counts: hist.into_std_hash_map() };
+    /// assert_eq!(ms.counts["foo"], 1);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn into_std_hash_map(self) -> std::collections::HashMap<K, C> {
+        self.map.into_iter().collect()
+    }
+
+    /// Merge two Histograms by moving the smaller one into the bigger one, which saves some
+    /// rehashing
+    fn merge(mut self, mut other: Self) -> Self {
+        if self.num_categories() < other.num_categories() {
+            core::mem::swap(&mut self, &mut other);
+        }
+        self.append(other);
+        self
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher + Default, C: Count> Histogram<K, S, C> {
+    /// Create a new Histogram by counting owned instanes of `K` in `iter`.
+    ///
+    /// This is similar to [`Histogram::from_iter()`] but taking owned values instead of references.
+    ///
+    /// See also [`Histogram::extend_from_owned()`] for motivations.
+    pub fn from_owned_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let mut h = Self::default();
+        h.extend_from_owned(iter);
+        h
+    }
+
+    /// Merge many Histograms into one, summing up the counts of equal `key`s
+    ///
+    /// This is the same as [`Histogram::append()`]ing them one after another, but always moves
+    /// the smaller Histogram into the bigger one, which saves rehashing when combining many
+    /// shards. An empty `hists` gives an empty Histogram. The [`OverflowPolicy`] of the largest
+    /// Histogram is kept.
+    ///
+    /// # Panics
+    /// If a count overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let shards = ["ab", "bc", "cd"].map(|s| Histogram::<_>::from_owned_iter(s.chars()));
+    ///
+    /// let hist = Histogram::merge_all(shards);
+    /// assert_eq!(hist.num_instances(), 6);
+    /// assert_eq!(hist.count(&'b'), 2);
+    /// ```
+    pub fn merge_all<I: IntoIterator<Item = Self>>(hists: I) -> Self {
+        hists.into_iter().reduce(Self::merge).unwrap_or_default()
+    }
+
+    /// Create a new Histogram with all `key`s from `self` and `other`, using the larger count
+    ///
+    /// This treats histograms as multisets, like `Counter`s in Python.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let a = Histogram::<_>::from_owned_iter("aaab".chars());
+    /// let b = Histogram::<_>::from_owned_iter("abbc".chars());
+    ///
+    /// let union: Histogram<_> = a.union(&b);
+    /// assert_eq!(union.num_categories(), 3);
+    /// assert_eq!(union.count(&'a'), 3);
+    /// assert_eq!(union.count(&'b'), 2);
+    /// assert_eq!(union.count(&'c'), 1);
+    /// ```
+    #[must_use]
+    pub fn union<S2: BuildHasher>(&self, other: &Histogram<K, S2, C>) -> Self
+    where
+        K: Clone,
+    {
+        let mut h = self.clone_with_default_hasher();
+        for (key, cnt) in other {
+            if let Some(old) = h.map.get_mut(key) {
+                if cnt > *old {
+                    *old = cnt;
+                }
+            } else {
+                h.map.insert(key.clone(), cnt);
+            }
+        }
+        h
+    }
+
+    /// Create a new Histogram with the `key`s present in both `self` and `other`, using the
+    /// smaller count
+    ///
+    /// This treats histograms as multisets, like `Counter`s in Python.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let a = Histogram::<_>::from_owned_iter("aaab".chars());
+    /// let b = Histogram::<_>::from_owned_iter("abbc".chars());
+    ///
+    /// let intersection: Histogram<_> = a.intersection(&b);
+    /// assert_eq!(intersection.count(&'a'), 1);
+    /// assert_eq!(intersection.count(&'b'), 1);
+    /// assert_eq!(intersection.count(&'c'), 0);
+    /// ```
+    #[must_use]
+    pub fn intersection<S2: BuildHasher>(&self, other: &Histogram<K, S2, C>) -> Self
+    where
+        K: Clone,
+    {
+        let map = self
+            .iter()
+            .filter_map(|(key, cnt)| {
+                let other_cnt = *other.map.get(key)?;
+                Some((key.clone(), if cnt < other_cnt { cnt } else { other_cnt }))
+            })
+            .collect();
+        self.new_like(map)
+    }
+
+    /// Create a new Histogram with the `key`s of `self` that are not present in `other`
+    ///
+    /// The counts of the remaining `key`s are taken from `self`. For subtracting the counts of
+    /// `other` see [`Histogram::subtract()`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let a = Histogram::<_>::from_owned_iter("aaab".chars());
+    /// let b = Histogram::<_>::from_owned_iter("bbc".chars());
+    ///
+    /// let difference: Histogram<_> = a.difference(&b);
+    /// assert_eq!(difference.sorted_occurrences(), vec![('a', 3)]);
+    /// ```
+    #[must_use]
+    pub fn difference<S2: BuildHasher, C2: Count>(&self, other: &Histogram<K, S2, C2>) -> Self
+    where
+        K: Clone,
+    {
+        let map = self
+            .iter()
+            .filter(|(key, _cnt)| !other.contains_key(*key))
+            .map(|(key, cnt)| (key.clone(), cnt))
+            .collect();
+        self.new_like(map)
+    }
+
+    /// Create a new Histogram by applying `f` to every `key`, merging counts of colliding `key`s
+    ///
+    /// This is useful to normalize `key`s after counting, for example by lowercasing or stemming.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<String>::from_iter(["Moin", "moin", "MOIN", "Hoi"]);
+    ///
+    /// let lower: Histogram<String> = hist.map_keys(|key| key.to_lowercase());
+    /// assert_eq!(lower.count("moin"), 3);
+    /// assert_eq!(lower.count("hoi"), 1);
+    /// assert_eq!(lower.num_categories(), 2);
+    /// ```
+    ///
+    /// # Panics
+    /// If a merged count overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    #[must_use]
+    pub fn map_keys<K2, F>(self, mut f: F) -> Histogram<K2, S, C>
+    where
+        K2: Hash + Eq,
+        F: FnMut(K) -> K2,
+    {
+        let mut h = Histogram::<K2, S, C> {
+            map: HashMap::default(),
+            overflow: self.overflow,
+        };
+        for (key, cnt) in self {
+            let old = h.map.entry(f(key)).or_insert(C::ZERO);
+            h.overflow.add(old, cnt);
+        }
+        h
+    }
+
+    /// Wrap `map` into a Histogram using the same [`OverflowPolicy`] as `self`
+    fn new_like(&self, map: HashMap<K, C, S>) -> Self {
+        Self {
+            map,
+            overflow: self.overflow,
+        }
+    }
+
+    /// Clone `self` into a Histogram using a fresh default hasher
+    fn clone_with_default_hasher(&self) -> Self
+    where
+        K: Clone,
+    {
+        let map = self.iter().map(|(key, cnt)| (key.clone(), cnt)).collect();
+        self.new_like(map)
+    }
+}
+
+/// The order used by [`Histogram::sorted_occurrences_in()`]
+///
+/// All orders are deterministic, so `key`s with the same count are sorted ascending by `key`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SortOrder {
+    /// Most common `key`s first
+    #[default]
+    CountDescending,
+    /// Least common `key`s first
+    CountAscending,
+    /// Smallest `key`s first
+    KeyAscending,
+    /// Largest `key`s first
+    KeyDescending,
+}
+
+impl SortOrder {
+    /// Compare two `(key, count)` pairs in this order
+    fn compare<K: Ord, C: Count>(self, (ka, a): (&K, &C), (kb, b): (&K, &C)) -> Ordering {
+        match self {
+            SortOrder::CountDescending => b.total_cmp(a).then_with(|| ka.cmp(kb)),
+            SortOrder::CountAscending => a.total_cmp(b).then_with(|| ka.cmp(kb)),
+            SortOrder::KeyAscending => ka.cmp(kb),
+            SortOrder::KeyDescending => kb.cmp(ka),
+        }
+    }
+}
+
+/// Orders `(count, key)` pairs only by their count, so `key`s do not need to implement [`Ord`]
+struct ByCount<'a, K, C>(C, &'a K);
+
+impl<K, C: Count> PartialEq for ByCount<'_, K, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K, C: Count> Eq for ByCount<'_, K, C> {}
+
+impl<K, C: Count> PartialOrd for ByCount<'_, K, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K, C: Count> Ord for ByCount<'_, K, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Orders `(key, count)` pairs so that a max-heap yields them in [`SortOrder::CountDescending`]
+struct CountDescending<K, C>(K, C);
+
+impl<K: Ord, C: Count> PartialEq for CountDescending<K, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K: Ord, C: Count> Eq for CountDescending<K, C> {}
+
+impl<K: Ord, C: Count> PartialOrd for CountDescending<K, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, C: Count> Ord for CountDescending<K, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed, as the heap pops the greatest element first
+        SortOrder::CountDescending.compare((&other.0, &other.1), (&self.0, &self.1))
+    }
+}
+
+/// Exact comparison of all counts, the [`OverflowPolicy`] does not matter
+///
+/// See [`Histogram::approx_eq()`] for comparing relative frequencies with a tolerance.
+// Not derived, as that would needlessly require the hasher `S` to be `PartialEq`
+impl<K: Hash + Eq, S: BuildHasher, C: Count> PartialEq for Histogram<K, S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher, C: Count + Eq> Eq for Histogram<K, S, C> {}
+
+// This can not be derived as it would then only be available if `K: Default` which we don't need here.
+impl<K: Hash + Eq, S: BuildHasher + Default, C: Count> Default for Histogram<K, S, C> {
+    fn default() -> Self {
+        Self {
+            map: HashMap::default(),
+            overflow: OverflowPolicy::Panic,
+        }
+    }
+}
+
+/// Count borrowed `key`s by calling [`Histogram::add_ref()`] for each of them
+///
+/// There is no `Extend<K>` for owned `key`s, as it would overlap with this implementation for
+/// Histograms of references like `Histogram<&str>`. Use [`Histogram::extend_from_owned()`] for
+/// those, or extend with `(key, 1)` pairs.
+///
+/// ```rust
+/// # use histongram::Histogram;
+/// fn fill<E: Extend<&'static str>>(target: &mut E) {
+///     target.extend("a b a".split(' '));
+/// }
+///
+/// let mut hist = Histogram::<String>::new();
+/// fill(&mut hist);
+/// assert_eq!(hist.count("a"), 2);
+/// ```
+impl<'a, K, S, C, Q> Extend<&'a Q> for Histogram<K, S, C>
+where
+    K: Hash + Eq + Borrow<Q> + From<&'a Q>,
+    Q: ?Sized + Hash + Eq + 'a,
+    S: BuildHasher,
+    C: Count,
+{
+    fn extend<T: IntoIterator<Item = &'a Q>>(&mut self, iter: T) {
+        for item in iter {
             self.add_ref(item);
         }
     }
 }
 
-impl<'a, K, S, Q> FromIterator<&'a Q> for Histogram<K, S>
+impl<'a, K, S, C, Q> FromIterator<&'a Q> for Histogram<K, S, C>
 where
     K: Hash + Eq + Borrow<Q> + From<&'a Q>,
     Q: ?Sized + Hash + Eq + 'a,
     S: BuildHasher + Default,
+    C: Count,
 {
     fn from_iter<T: IntoIterator<Item = &'a Q>>(iter: T) -> Self {
         let mut h = Self {
             map: HashMap::with_hasher(Default::default()),
+            overflow: OverflowPolicy::Panic,
         };
         h.extend(iter);
         h
     }
 }
 
-impl<'a, K: Hash + Eq + 'a, S: BuildHasher> IntoIterator for &'a Histogram<K, S> {
-    type Item = (&'a K, usize);
-    type IntoIter = iter::Map<hash_map::Iter<'a, K, usize>, fn((&'a K, &'a usize)) -> Self::Item>;
+/// Add counts of owned `key`s, see [`Histogram::extend_from_counts()`]
+///
+/// ```rust
+/// # use histongram::Histogram;
+/// let mut hist = Histogram::<char>::new();
+/// hist.extend("abca".chars().map(|c| (c, 1)));
+/// hist.extend([('c', 3)]);
+/// assert_eq!(hist.count(&'c'), 4);
+/// ```
+impl<K, S, C> Extend<(K, C)> for Histogram<K, S, C>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    C: Count,
+{
+    fn extend<T: IntoIterator<Item = (K, C)>>(&mut self, iter: T) {
+        self.extend_from_counts(iter);
+    }
+}
+
+impl<K, S, C> FromIterator<(K, C)> for Histogram<K, S, C>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+    C: Count,
+{
+    fn from_iter<T: IntoIterator<Item = (K, C)>>(iter: T) -> Self {
+        let mut h = Self::default();
+        h.extend_from_counts(iter);
+        h
+    }
+}
+
+/// Merge two histograms, see [`Histogram::append()`]
+impl<K: Hash + Eq, S: BuildHasher, C: Count> Add for Histogram<K, S, C> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self.append(rhs);
+        self
+    }
+}
+
+/// Merge two histograms, see [`Histogram::add_counts_from()`]
+impl<K, S, S2, C> Add<&Histogram<K, S2, C>> for Histogram<K, S, C>
+where
+    K: Hash + Eq + Clone,
+    S: BuildHasher,
+    S2: BuildHasher,
+    C: Count,
+{
+    type Output = Self;
+
+    fn add(mut self, rhs: &Histogram<K, S2, C>) -> Self::Output {
+        self.add_counts_from(rhs);
+        self
+    }
+}
+
+/// Merge two histograms, see [`Histogram::append()`]
+impl<K: Hash + Eq, S: BuildHasher, C: Count> AddAssign for Histogram<K, S, C> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.append(rhs);
+    }
+}
+
+/// Merge two histograms, see [`Histogram::add_counts_from()`]
+impl<K, S, S2, C> AddAssign<&Histogram<K, S2, C>> for Histogram<K, S, C>
+where
+    K: Hash + Eq + Clone,
+    S: BuildHasher,
+    S2: BuildHasher,
+    C: Count,
+{
+    fn add_assign(&mut self, rhs: &Histogram<K, S2, C>) {
+        self.add_counts_from(rhs);
+    }
+}
+
+/// Saturating difference of two histograms, see [`Histogram::subtract()`]
+impl<K, S, S2, C> Sub<&Histogram<K, S2, C>> for Histogram<K, S, C>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    S2: BuildHasher,
+    C: Count,
+{
+    type Output = Self;
+
+    fn sub(mut self, rhs: &Histogram<K, S2, C>) -> Self::Output {
+        self.subtract(rhs);
+        self
+    }
+}
+
+/// Saturating difference of two histograms, see [`Histogram::subtract()`]
+impl<K, S, S2, C> SubAssign<&Histogram<K, S2, C>> for Histogram<K, S, C>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    S2: BuildHasher,
+    C: Count,
+{
+    fn sub_assign(&mut self, rhs: &Histogram<K, S2, C>) {
+        self.subtract(rhs);
+    }
+}
+
+impl<'a, K: Hash + Eq + 'a, S: BuildHasher, C: Count> IntoIterator for &'a Histogram<K, S, C> {
+    type Item = (&'a K, C);
+    type IntoIter = iter::Map<hash_map::Iter<'a, K, C>, fn((&'a K, &'a C)) -> Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
         // This can not be a closure as we need to name its type in `IntoIter`
-        fn deref_cnt<'a, K>((key, cnt): (&'a K, &'a usize)) -> (&'a K, usize) {
+        fn deref_cnt<'a, K, C: Copy>((key, cnt): (&'a K, &'a C)) -> (&'a K, C) {
             (key, *cnt)
         }
 
@@ -440,41 +1980,83 @@ impl<'a, K: Hash + Eq + 'a, S: BuildHasher> IntoIterator for &'a Histogram<K, S>
     }
 }
 
-impl<K: Hash + Eq, S: BuildHasher> IntoIterator for Histogram<K, S> {
-    type Item = (K, usize);
-    type IntoIter = hash_map::IntoIter<K, usize>;
+impl<K: Hash + Eq, S: BuildHasher, C: Count> IntoIterator for Histogram<K, S, C> {
+    type Item = (K, C);
+    type IntoIter = hash_map::IntoIter<K, C>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.map.into_iter()
     }
 }
 
-impl<K: Hash + Eq, S: BuildHasher> From<HashMap<K, usize, S>> for Histogram<K, S> {
-    fn from(map: HashMap<K, usize, S>) -> Self {
-        Self { map }
+impl<K: Hash + Eq, S: BuildHasher, C: Count> From<HashMap<K, C, S>> for Histogram<K, S, C> {
+    fn from(map: HashMap<K, C, S>) -> Self {
+        Self {
+            map,
+            overflow: OverflowPolicy::Panic,
+        }
     }
 }
 
-impl<K: Hash + Eq, H: BuildHasher> From<Histogram<K, H>> for HashMap<K, usize, H> {
-    fn from(hist: Histogram<K, H>) -> Self {
+impl<K: Hash + Eq, H: BuildHasher, C: Count> From<Histogram<K, H, C>> for HashMap<K, C, H> {
+    fn from(hist: Histogram<K, H, C>) -> Self {
         hist.map
     }
 }
 
+// NOTE: `Index` has to return a reference, which for absent `key`s has to point to a zero that
+// lives long enough. A generic `&C::ZERO` can not be promoted to a `'static`, so this is only
+// implemented for the built-in count types.
+macro_rules! impl_index {
+    ($($ty:ty => $zero:literal),*) => {$(
+        /// Get the count of `key` via `hist[&key]`, which is `0` for absent `key`s
+        ///
+        /// Like [`Histogram::count()`] this also accepts any borrowed form of the `key` type.
+        ///
+        /// # Example
+        /// ```rust
+        /// # use histongram::Histogram;
+        /// let hist = Histogram::<_>::from_owned_iter(["word", "word", "other"]);
+        /// assert_eq!(hist[&"word"], 2);
+        /// assert_eq!(hist[&"absent"], 0);
+        ///
+        /// let hist = Histogram::<String>::from_iter(["word", "word", "other"]);
+        /// assert_eq!(hist["word"], 2);
+        /// ```
+        impl<K, S, Q> Index<&Q> for Histogram<K, S, $ty>
+        where
+            K: Hash + Eq + Borrow<Q>,
+            S: BuildHasher,
+            Q: ?Sized + Hash + Eq,
+        {
+            type Output = $ty;
+
+            fn index(&self, key: &Q) -> &$ty {
+                self.map.get(key).unwrap_or(&$zero)
+            }
+        }
+    )*};
+}
+
+impl_index!(
+    u8 => 0, u16 => 0, u32 => 0, u64 => 0, u128 => 0, usize => 0, f32 => 0.0, f64 => 0.0
+);
+
 #[cfg(feature = "serde")]
 mod serde {
-    use std::hash::{BuildHasher, Hash};
+    use core::hash::{BuildHasher, Hash};
 
     use hashbrown::HashMap;
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-    use super::Histogram;
+    use super::{Count, Histogram};
 
     #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
-    impl<K, S> Serialize for Histogram<K, S>
+    impl<K, S, C> Serialize for Histogram<K, S, C>
     where
         K: Hash + Eq + Serialize,
         S: BuildHasher,
+        C: Count + Serialize,
     {
         fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
         where
@@ -485,18 +2067,210 @@ mod serde {
     }
 
     #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
-    impl<'de, K, S> Deserialize<'de> for Histogram<K, S>
+    impl<'de, K, S, C> Deserialize<'de> for Histogram<K, S, C>
     where
         K: Hash + Eq + Deserialize<'de>,
         S: BuildHasher + Default,
+        C: Count + Deserialize<'de>,
     {
         fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
         where
             D: Deserializer<'de>,
         {
-            Ok(Self {
-                map: HashMap::deserialize(deserializer)?,
+            Ok(HashMap::deserialize(deserializer)?.into())
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+mod rayon {
+    use core::borrow::Borrow;
+    use core::hash::{BuildHasher, Hash};
+
+    use rayon::iter::{
+        FromParallelIterator, IntoParallelIterator, ParallelExtend, ParallelIterator,
+    };
+
+    use super::{Count, Histogram, OverflowPolicy};
+
+    /// Count `chunks` in parallel and merge the results into a single [`Histogram`]
+    ///
+    /// Each chunk is turned into a local Histogram by `per_chunk` on one of the worker threads of
+    /// [`rayon`]. As [`Histogram::append()`] is associative and commutative the partial results
+    /// can be merged in any order, giving the same result as counting everything serially.
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::{parallel_count, Histogram};
+    ///
+    /// let lines = vec!["a b c", "a b", "a"];
+    ///
+    /// let hist: Histogram<String> = parallel_count(lines, |line| {
+    ///     line.split_whitespace().collect()
+    /// });
+    ///
+    /// assert_eq!(hist.count("a"), 3);
+    /// assert_eq!(hist.count("b"), 2);
+    /// assert_eq!(hist.count("c"), 1);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    pub fn parallel_count<I, F, K, S, C>(chunks: I, per_chunk: F) -> Histogram<K, S, C>
+    where
+        I: IntoParallelIterator,
+        F: Fn(I::Item) -> Histogram<K, S, C> + Sync + Send,
+        K: Hash + Eq + Send,
+        S: BuildHasher + Default + Send,
+        C: Count + Send,
+    {
+        chunks
+            .into_par_iter()
+            .map(per_chunk)
+            .reduce(Histogram::default, Histogram::merge)
+    }
+
+    impl<K, S, C> Histogram<K, S, C>
+    where
+        K: Hash + Eq + Send,
+        S: BuildHasher + Default + Send,
+        C: Count + Send,
+    {
+        /// Merge many Histograms into one in parallel, see [`Histogram::merge_all()`]
+        ///
+        /// The Histograms are merged pairwise as a tree on the worker threads of [`rayon`], so
+        /// combining hundreds of shards does not end up in one long sequential chain.
+        ///
+        /// # Panics
+        /// If a count overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+        ///
+        /// # Example
+        /// ```rust
+        /// # use histongram::Histogram;
+        /// let shards: Vec<_> = ["ab", "bc", "cd"]
+        ///     .iter()
+        ///     .map(|s| Histogram::<_>::from_owned_iter(s.chars()))
+        ///     .collect();
+        ///
+        /// let hist = Histogram::par_merge_all(shards);
+        /// assert_eq!(hist.num_instances(), 6);
+        /// assert_eq!(hist.count(&'b'), 2);
+        /// ```
+        #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+        pub fn par_merge_all<I: IntoParallelIterator<Item = Self>>(hists: I) -> Self {
+            hists
+                .into_par_iter()
+                .reduce_with(Histogram::merge)
+                .unwrap_or_default()
+        }
+    }
+
+    /// Count the items of a parallel iterator into per-thread Histograms and merge them
+    fn count_par<I, T, F, K, S, C>(
+        par_iter: I,
+        overflow: OverflowPolicy,
+        add: F,
+    ) -> Histogram<K, S, C>
+    where
+        I: IntoParallelIterator<Item = T>,
+        T: Send,
+        F: Fn(&mut Histogram<K, S, C>, T) + Sync + Send,
+        K: Hash + Eq + Send,
+        S: BuildHasher + Default + Send,
+        C: Count + Send,
+    {
+        let empty = || Histogram {
+            map: hashbrown::HashMap::default(),
+            overflow,
+        };
+        par_iter
+            .into_par_iter()
+            .fold(empty, |mut hist, item| {
+                add(&mut hist, item);
+                hist
+            })
+            .reduce(empty, Histogram::merge)
+    }
+
+    /// Count the items of a parallel iterator, like the [`FromIterator`] implementation
+    ///
+    /// Every worker thread counts into its own Histogram, which are merged at the end.
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::Histogram;
+    /// use rayon::prelude::*;
+    ///
+    /// let words = vec!["a", "b", "a"];
+    /// let hist: Histogram<String> = words.into_par_iter().collect();
+    /// assert_eq!(hist.count("a"), 2);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    impl<'a, K, S, C, Q> FromParallelIterator<&'a Q> for Histogram<K, S, C>
+    where
+        K: Hash + Eq + Borrow<Q> + From<&'a Q> + Send,
+        Q: ?Sized + Hash + Eq + Sync + 'a,
+        S: BuildHasher + Default + Send,
+        C: Count + Send,
+    {
+        fn from_par_iter<I: IntoParallelIterator<Item = &'a Q>>(par_iter: I) -> Self {
+            count_par(par_iter, OverflowPolicy::default(), |hist, item| {
+                hist.add_ref(item);
+            })
+        }
+    }
+
+    /// Count the items of a parallel iterator into an existing Histogram
+    ///
+    /// The partial results keep the [`OverflowPolicy`] of `self`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    impl<'a, K, S, C, Q> ParallelExtend<&'a Q> for Histogram<K, S, C>
+    where
+        K: Hash + Eq + Borrow<Q> + From<&'a Q> + Send,
+        Q: ?Sized + Hash + Eq + Sync + 'a,
+        S: BuildHasher + Default + Send,
+        C: Count + Send,
+    {
+        fn par_extend<I: IntoParallelIterator<Item = &'a Q>>(&mut self, par_iter: I) {
+            let partial = count_par(par_iter, self.overflow, |hist, item| {
+                hist.add_ref(item);
+            });
+            self.append(partial);
+        }
+    }
+
+    /// Sum up `(key, count)` pairs of a parallel iterator, like the [`FromIterator`]
+    /// implementation
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    impl<K, S, C> FromParallelIterator<(K, C)> for Histogram<K, S, C>
+    where
+        K: Hash + Eq + Send,
+        S: BuildHasher + Default + Send,
+        C: Count + Send,
+    {
+        fn from_par_iter<I: IntoParallelIterator<Item = (K, C)>>(par_iter: I) -> Self {
+            count_par(par_iter, OverflowPolicy::default(), |hist, (key, n)| {
+                hist.add_owned_n(key, n);
             })
         }
     }
+
+    /// Sum up `(key, count)` pairs of a parallel iterator into an existing Histogram
+    ///
+    /// The partial results keep the [`OverflowPolicy`] of `self`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    impl<K, S, C> ParallelExtend<(K, C)> for Histogram<K, S, C>
+    where
+        K: Hash + Eq + Send,
+        S: BuildHasher + Default + Send,
+        C: Count + Send,
+    {
+        fn par_extend<I: IntoParallelIterator<Item = (K, C)>>(&mut self, par_iter: I) {
+            let partial = count_par(par_iter, self.overflow, |hist, (key, n)| {
+                hist.add_owned_n(key, n);
+            });
+            self.append(partial);
+        }
+    }
 }
+
+#[cfg(feature = "rayon")]
+pub use crate::rayon::parallel_count;
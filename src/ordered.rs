@@ -0,0 +1,256 @@
+//! A Histogram backed by a [`BTreeMap`], for sorted iteration and range queries.
+
+use alloc::collections::{btree_map, BTreeMap};
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::ops::RangeBounds;
+
+use crate::{Count, Histogram, OverflowPolicy};
+
+/// A Histogram that keeps its `key`s sorted
+///
+/// This has the same counting API as [`Histogram`], but stores the counts in a [`BTreeMap`]. So
+/// lookups take `O(log n)` instead of `O(1)`, but iteration is always sorted by `key` and the
+/// counts of a range of `key`s can be summed up, which is useful for dates, numeric IDs and
+/// similar `key`s.
+///
+/// # Example
+/// ```rust
+/// use histongram::OrderedHistogram;
+///
+/// let mut by_day = OrderedHistogram::<u32>::new();
+/// by_day.extend_from_owned([20240101, 20240102, 20240102, 20240201, 20240215]);
+///
+/// assert_eq!(by_day.range_count(20240101..20240201), 3);
+/// assert_eq!(by_day.first(), Some((&20240101, 1)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct OrderedHistogram<K: Ord, C: Count = usize> {
+    map: BTreeMap<K, C>,
+    overflow: OverflowPolicy,
+}
+
+impl<K: Ord, C: Count> Default for OrderedHistogram<K, C> {
+    fn default() -> Self {
+        Self {
+            map: BTreeMap::new(),
+            overflow: OverflowPolicy::Panic,
+        }
+    }
+}
+
+/// Exact comparison of all counts, the [`OverflowPolicy`] does not matter
+// Not derived, as that would compare the `OverflowPolicy` too
+impl<K: Ord, C: Count> PartialEq for OrderedHistogram<K, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<K: Ord, C: Count + Eq> Eq for OrderedHistogram<K, C> {}
+
+// Not derived, as it has to agree with `PartialEq` and ignore the `OverflowPolicy`
+impl<K: Ord + Hash, C: Count + Hash> Hash for OrderedHistogram<K, C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.map.hash(state);
+    }
+}
+
+impl<K: Ord> OrderedHistogram<K> {
+    /// Create a new empty `OrderedHistogram`
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<K: Ord, C: Count> OrderedHistogram<K, C> {
+    /// Add a new occurrence of `key`, see [`Histogram::add_ref()`]
+    ///
+    /// # Panics
+    /// If the count of `key` overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    pub fn add_ref<'a, Q>(&mut self, val: &'a Q)
+    where
+        K: Borrow<Q> + From<&'a Q>,
+        Q: ?Sized + Ord,
+    {
+        if let Some(cnt) = self.map.get_mut(val) {
+            self.overflow.add(cnt, C::ONE);
+        } else {
+            self.map.insert(val.into(), C::ONE);
+        }
+    }
+
+    /// Add a new occurrence of `key`, see [`Histogram::add_owned()`]
+    ///
+    /// # Panics
+    /// If the count of `key` overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    pub fn add_owned(&mut self, val: K) {
+        self.add_owned_n(val, C::ONE);
+    }
+
+    /// Add `n` occurrences of `key`, see [`Histogram::add_owned_n()`]
+    ///
+    /// # Panics
+    /// If the count of `key` overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    pub fn add_owned_n(&mut self, val: K, n: C) {
+        // Only categories that occurred at least once are kept, this also skips NaN
+        if n.partial_cmp(&C::ZERO) != Some(Ordering::Greater) {
+            return;
+        }
+        let cnt = self.map.entry(val).or_insert(C::ZERO);
+        self.overflow.add(cnt, n);
+    }
+
+    /// Count all owned `key`s of `iter`, see [`Histogram::extend_from_owned()`]
+    ///
+    /// # Panics
+    /// If a count overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    pub fn extend_from_owned<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for val in iter {
+            self.add_owned(val);
+        }
+    }
+
+    /// Set the [`OverflowPolicy`] used when counts get too large for `C`
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.overflow = policy;
+    }
+
+    /// Number of times `key` occurred, see [`Histogram::count()`]
+    pub fn count<Q>(&self, key: &Q) -> C
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.map.get(key).copied().unwrap_or(C::ZERO)
+    }
+
+    /// Number of different `key`s
+    #[must_use]
+    pub fn num_categories(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Sum of all counts
+    ///
+    /// # Panics
+    /// If the total overflows `C` and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    #[must_use]
+    pub fn num_instances(&self) -> C {
+        self.overflow.sum(self.map.values().copied())
+    }
+
+    /// Check if nothing was added so far
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Iterate over all `key`s and their counts, sorted ascending by `key`
+    #[must_use]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, C)> {
+        self.map.iter().map(|(key, &cnt)| (key, cnt))
+    }
+
+    /// Iterate over the `key`s in `range` and their counts, sorted ascending by `key`
+    ///
+    /// # Panics
+    /// If the start of `range` is after its end, see [`BTreeMap::range()`].
+    pub fn range<Q, R>(&self, range: R) -> impl DoubleEndedIterator<Item = (&K, C)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: RangeBounds<Q>,
+    {
+        self.map.range(range).map(|(key, &cnt)| (key, cnt))
+    }
+
+    /// Sum of the counts of all `key`s in `range`
+    ///
+    /// # Panics
+    /// If the start of `range` is after its end, see [`BTreeMap::range()`], or if the sum overflows
+    /// `C` and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    pub fn range_count<Q, R>(&self, range: R) -> C
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: RangeBounds<Q>,
+    {
+        self.overflow.sum(self.range(range).map(|(_key, cnt)| cnt))
+    }
+
+    /// The smallest `key` and its count
+    #[must_use]
+    pub fn first(&self) -> Option<(&K, C)> {
+        self.map.first_key_value().map(|(key, &cnt)| (key, cnt))
+    }
+
+    /// The largest `key` and its count
+    #[must_use]
+    pub fn last(&self) -> Option<(&K, C)> {
+        self.map.last_key_value().map(|(key, &cnt)| (key, cnt))
+    }
+
+    /// Convert into a hash based [`Histogram`]
+    #[must_use]
+    pub fn into_histogram<S>(self) -> Histogram<K, S, C>
+    where
+        K: Hash,
+        S: BuildHasher + Default,
+    {
+        let mut hist: Histogram<K, S, C> = self.map.into_iter().collect();
+        hist.set_overflow_policy(self.overflow);
+        hist
+    }
+}
+
+impl<K: Ord, C: Count> IntoIterator for OrderedHistogram<K, C> {
+    type Item = (K, C);
+    type IntoIter = btree_map::IntoIter<K, C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_iter()
+    }
+}
+
+impl<'a, K, C, Q> Extend<&'a Q> for OrderedHistogram<K, C>
+where
+    K: Ord + Borrow<Q> + From<&'a Q>,
+    Q: ?Sized + Ord + 'a,
+    C: Count,
+{
+    fn extend<T: IntoIterator<Item = &'a Q>>(&mut self, iter: T) {
+        for item in iter {
+            self.add_ref(item);
+        }
+    }
+}
+
+impl<'a, K, C, Q> FromIterator<&'a Q> for OrderedHistogram<K, C>
+where
+    K: Ord + Borrow<Q> + From<&'a Q>,
+    Q: ?Sized + Ord + 'a,
+    C: Count,
+{
+    fn from_iter<T: IntoIterator<Item = &'a Q>>(iter: T) -> Self {
+        let mut hist = Self::default();
+        hist.extend(iter);
+        hist
+    }
+}
+
+impl<K, S, C> From<Histogram<K, S, C>> for OrderedHistogram<K, C>
+where
+    K: Hash + Eq + Ord,
+    S: BuildHasher,
+    C: Count,
+{
+    fn from(hist: Histogram<K, S, C>) -> Self {
+        Self {
+            overflow: hist.overflow,
+            map: hist.into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Counting lines and words of large inputs without loading them into memory.
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+use std::io::{self, BufRead};
+
+use crate::{Count, Histogram};
+
+impl<K, S, C> Histogram<K, S, C>
+where
+    K: Hash + Eq + Borrow<str> + for<'a> From<&'a str>,
+    S: BuildHasher + Default,
+    C: Count,
+{
+    /// Count every line of `reader`
+    ///
+    /// The line endings `\n` and `\r\n` are not part of the `key`s. Lines are read one by one
+    /// into a reused buffer, so apart from the distinct lines themselves the memory use is
+    /// constant, even for inputs that are many gigabytes large.
+    ///
+    /// # Errors
+    /// If reading fails or the input is not valid UTF-8.
+    ///
+    /// # Panics
+    /// If a count overflows.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let log = "GET /\nPOST /login\r\nGET /\n";
+    /// let hist = Histogram::<String>::from_lines(log.as_bytes()).unwrap();
+    ///
+    /// assert_eq!(hist.count("GET /"), 2);
+    /// assert_eq!(hist.count("POST /login"), 1);
+    /// ```
+    pub fn from_lines<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut hist = Self::default();
+        for_each_line(reader, |line| hist.add_ref(line))?;
+        Ok(hist)
+    }
+
+    /// Count every whitespace separated word of `reader`
+    ///
+    /// Words are split like [`str::split_whitespace()`], reading one line at a time. See
+    /// [`Histogram::from_lines()`] for the memory use.
+    ///
+    /// # Errors
+    /// If reading fails or the input is not valid UTF-8.
+    ///
+    /// # Panics
+    /// If a count overflows.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let text = "the cat\nsat on  the\tmat\n";
+    /// let hist = Histogram::<String>::from_words(text.as_bytes()).unwrap();
+    ///
+    /// assert_eq!(hist.count("the"), 2);
+    /// assert_eq!(hist.num_instances(), 6);
+    /// ```
+    pub fn from_words<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut hist = Self::default();
+        for_each_line(reader, |line| {
+            for word in line.split_whitespace() {
+                hist.add_ref(word);
+            }
+        })?;
+        Ok(hist)
+    }
+}
+
+/// Call `f` with every line of `reader`, without its line ending
+fn for_each_line<R: BufRead, F: FnMut(&str)>(mut reader: R, mut f: F) -> io::Result<()> {
+    let mut buf = String::new();
+    loop {
+        buf.clear();
+        if reader.read_line(&mut buf)? == 0 {
+            return Ok(());
+        }
+        let line = buf.strip_suffix('\n').unwrap_or(&buf);
+        f(line.strip_suffix('\r').unwrap_or(line));
+    }
+}
@@ -0,0 +1,214 @@
+//! Keeping a uniform random sample of the raw instances of a stream next to their counts.
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+
+use rand::Rng;
+
+use crate::{DefaultHashBuilder, Histogram};
+
+/// A uniform random sample of at most `capacity` instances of a stream
+///
+/// This implements reservoir sampling ("Algorithm R"): after `n` instances were added, every one
+/// of them is in the sample with the same probability `capacity / n`, using only `O(capacity)`
+/// memory. In contrast to [`Histogram::sample()`] this keeps the raw instances, so they can carry
+/// more than the `key` that is counted, like the line they were found in.
+///
+/// # Example
+/// ```rust
+/// use histongram::ReservoirSampler;
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+///
+/// let mut rng = StdRng::seed_from_u64(7);
+/// let mut examples = ReservoirSampler::new(3);
+/// for line in ["a cat", "a dog", "the cat", "some cat", "no cat"] {
+///     examples.add(line, &mut rng);
+/// }
+///
+/// assert_eq!(examples.sample().len(), 3);
+/// assert_eq!(examples.num_seen(), 5);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReservoirSampler<T> {
+    sample: Vec<T>,
+    capacity: usize,
+    seen: usize,
+}
+
+impl<T> ReservoirSampler<T> {
+    /// Create a new empty sampler keeping at most `capacity` instances
+    ///
+    /// The sample grows as instances are added, so nothing is allocated up front.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            sample: Vec::new(),
+            capacity,
+            seen: 0,
+        }
+    }
+
+    /// Offer `val` to the sample, using `rng` to decide if it is kept
+    pub fn add<R: Rng + ?Sized>(&mut self, val: T, rng: &mut R) {
+        self.add_with(rng, || val);
+    }
+
+    /// Offer an instance to the sample, which is only created by calling `make` if it is kept
+    ///
+    /// This avoids creating instances that are expensive to build but most likely dropped, as
+    /// only about `capacity / n` of the `n`th instance are kept.
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::ReservoirSampler;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let mut rng = StdRng::seed_from_u64(7);
+    /// let mut examples = ReservoirSampler::new(1);
+    /// for line in ["a cat", "a dog", "the cat"] {
+    ///     examples.add_with(&mut rng, || line.to_uppercase());
+    /// }
+    ///
+    /// assert_eq!(examples.sample().len(), 1);
+    /// ```
+    pub fn add_with<R, F>(&mut self, rng: &mut R, make: F)
+    where
+        R: Rng + ?Sized,
+        F: FnOnce() -> T,
+    {
+        self.seen += 1;
+        if self.sample.len() < self.capacity {
+            self.sample.push(make());
+            return;
+        }
+
+        let idx = rng.gen_range(0..self.seen);
+        if let Some(slot) = self.sample.get_mut(idx) {
+            *slot = make();
+        }
+    }
+
+    /// The sampled instances in arbitrary order
+    #[must_use]
+    pub fn sample(&self) -> &[T] {
+        &self.sample
+    }
+
+    /// Maximum number of sampled instances
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of instances offered to the sampler so far
+    #[must_use]
+    pub fn num_seen(&self) -> usize {
+        self.seen
+    }
+
+    /// Get the sampled instances, dropping the sampler
+    #[must_use]
+    pub fn into_sample(self) -> Vec<T> {
+        self.sample
+    }
+}
+
+/// A [`Histogram`] that also keeps a uniform [`ReservoirSampler`] sample of the counted instances
+///
+/// # Example
+/// ```rust
+/// use histongram::CountAndSample;
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+///
+/// let mut words = CountAndSample::<String, _>::new(2, StdRng::seed_from_u64(1));
+/// for word in "to be or not to be".split(' ') {
+///     words.add_ref(word);
+/// }
+///
+/// assert_eq!(words.histogram().count("be"), 2);
+/// assert_eq!(words.sample().len(), 2);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+#[derive(Debug, Clone)]
+pub struct CountAndSample<K: Hash + Eq, R, S: BuildHasher = DefaultHashBuilder> {
+    hist: Histogram<K, S>,
+    reservoir: ReservoirSampler<K>,
+    rng: R,
+}
+
+impl<K: Hash + Eq, R: Rng> CountAndSample<K, R> {
+    /// Create a new empty `CountAndSample` keeping at most `capacity` instances, drawn with `rng`
+    #[must_use]
+    pub fn new(capacity: usize, rng: R) -> Self {
+        Self::with_hasher(capacity, rng, DefaultHashBuilder::default())
+    }
+}
+
+impl<K: Hash + Eq, R: Rng, S: BuildHasher> CountAndSample<K, R, S> {
+    /// Create a new empty `CountAndSample` using the given `hash_builder`
+    #[must_use]
+    pub fn with_hasher(capacity: usize, rng: R, hash_builder: S) -> Self {
+        Self {
+            hist: Histogram::with_hasher(hash_builder),
+            reservoir: ReservoirSampler::new(capacity),
+            rng,
+        }
+    }
+
+    /// Count `val` and offer it to the sample, see [`Histogram::add_ref()`]
+    ///
+    /// An owned `key` is only created if `val` was not counted before or is kept in the sample.
+    ///
+    /// # Panics
+    /// If the count of `key` overflows.
+    pub fn add_ref<'a, Q>(&mut self, val: &'a Q)
+    where
+        K: Borrow<Q> + From<&'a Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.hist.add_ref(val);
+        self.reservoir.add_with(&mut self.rng, || val.into());
+    }
+
+    /// Count `val` and offer it to the sample, see [`Histogram::add_owned()`]
+    ///
+    /// `val` is only cloned if it was not counted before and is also kept in the sample.
+    ///
+    /// # Panics
+    /// If the count of `key` overflows.
+    pub fn add_owned(&mut self, val: K)
+    where
+        K: Clone,
+    {
+        let hist = &mut self.hist;
+        if let Some(cnt) = hist.map.get_mut(&val) {
+            hist.overflow.add(cnt, 1);
+            self.reservoir.add(val, &mut self.rng);
+        } else {
+            self.reservoir.add_with(&mut self.rng, || val.clone());
+            hist.map.insert(val, 1);
+        }
+    }
+
+    /// The counts of all instances
+    #[must_use]
+    pub fn histogram(&self) -> &Histogram<K, S> {
+        &self.hist
+    }
+
+    /// The sampled instances in arbitrary order
+    #[must_use]
+    pub fn sample(&self) -> &[K] {
+        self.reservoir.sample()
+    }
+
+    /// Split into the counts and the sampled instances
+    #[must_use]
+    pub fn into_parts(self) -> (Histogram<K, S>, Vec<K>) {
+        (self.hist, self.reservoir.into_sample())
+    }
+}
@@ -0,0 +1,161 @@
+//! Drawing random `key`s from a [`Histogram`] with probability proportional to their counts.
+
+use std::hash::{BuildHasher, Hash};
+
+use rand::distributions::Distribution;
+use rand::Rng;
+
+use crate::{Count, Histogram};
+
+/// A precomputed alias table for drawing `key`s of a [`Histogram`] proportional to their counts
+///
+/// Building the table takes `O(n)` time for `n` categories, after that every draw is `O(1)`.
+/// Use this (or [`Histogram::sample_iter()`]) instead of [`Histogram::sample()`] when drawing
+/// many times from the same Histogram.
+///
+/// # Example
+/// ```rust
+/// # use histongram::{Histogram, SampledDistribution};
+/// use rand::distributions::Distribution;
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+///
+/// let hist = Histogram::<_>::from_owned_iter("aab".chars());
+/// let dist = SampledDistribution::new(&hist).unwrap();
+///
+/// let mut rng = StdRng::seed_from_u64(42);
+/// let key = dist.sample(&mut rng);
+/// assert!(hist.contains_key(key));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+#[derive(Debug, Clone)]
+pub struct SampledDistribution<'a, K> {
+    keys: Vec<&'a K>,
+    prob: Vec<f64>,
+    alias: Vec<usize>,
+}
+
+impl<'a, K> SampledDistribution<'a, K> {
+    /// Build the alias table for `hist`, returns `None` if it is empty
+    #[must_use]
+    pub fn new<S: BuildHasher, C: Count>(hist: &'a Histogram<K, S, C>) -> Option<Self>
+    where
+        K: Hash + Eq,
+    {
+        if hist.is_empty() {
+            return None;
+        }
+
+        let (keys, weights): (Vec<_>, Vec<_>) = hist.iter().map(|(k, c)| (k, c.to_f64())).unzip();
+        // Rounding is fine when the numbers get to large to fit f64
+        #[allow(clippy::cast_precision_loss)]
+        let len = keys.len() as f64;
+        let total: f64 = weights.iter().sum();
+
+        // Vose's alias method: scale every weight so the mean is 1.0, then pair each underfull
+        // column with an overfull one that tops it up.
+        let mut prob: Vec<f64> = weights.iter().map(|w| w * len / total).collect();
+        let mut alias: Vec<usize> = (0..keys.len()).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..keys.len()).partition(|&i| prob[i] < 1.0);
+
+        while let (Some(s), Some(&l)) = (small.pop(), large.last()) {
+            alias[s] = l;
+            prob[l] -= 1.0 - prob[s];
+            if prob[l] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+        // Whatever is left over is only off from 1.0 due to rounding
+        for i in small.into_iter().chain(large) {
+            prob[i] = 1.0;
+        }
+
+        Some(Self { keys, prob, alias })
+    }
+
+    /// Number of distinct `key`s that can be drawn
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Always `false`, as an empty Histogram has no distribution
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+impl<'a, K> Distribution<&'a K> for SampledDistribution<'a, K> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &'a K {
+        let column = rng.gen_range(0..self.keys.len());
+        if rng.gen::<f64>() < self.prob[column] {
+            self.keys[column]
+        } else {
+            self.keys[self.alias[column]]
+        }
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher, C: Count> Histogram<K, S, C> {
+    /// Draw a single random `key` with probability proportional to its count
+    ///
+    /// This walks all categories, so it takes `O(n)` time. For repeated draws use
+    /// [`Histogram::sample_iter()`] or a [`SampledDistribution`]. Returns `None` for an empty
+    /// Histogram.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let hist = Histogram::<_>::from_owned_iter("aaa".chars());
+    /// let mut rng = StdRng::seed_from_u64(42);
+    /// assert_eq!(hist.sample(&mut rng), Some(&'a'));
+    ///
+    /// let empty = Histogram::<char>::new();
+    /// assert_eq!(empty.sample(&mut rng), None);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&K> {
+        let target = rng.gen::<f64>() * self.instances_f64();
+
+        let mut seen = 0.0;
+        let mut last = None;
+        for (key, cnt) in self.iter() {
+            seen += cnt.to_f64();
+            if target < seen {
+                return Some(key);
+            }
+            last = Some(key);
+        }
+        // Only reached through rounding, or if the Histogram is empty
+        last
+    }
+
+    /// Endless iterator of random `key`s drawn with probability proportional to their counts
+    ///
+    /// This builds a [`SampledDistribution`] once, so every draw takes `O(1)` time. The iterator
+    /// is empty if the Histogram is empty.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let hist = Histogram::<_>::from_owned_iter("the cat".split(' ').map(String::from));
+    /// let text: Vec<_> = hist.sample_iter(StdRng::seed_from_u64(42)).take(5).collect();
+    /// assert_eq!(text.len(), 5);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+    pub fn sample_iter<R: Rng>(&self, rng: R) -> impl Iterator<Item = &K> {
+        SampledDistribution::new(self)
+            .map(|dist| dist.sample_iter(rng))
+            .into_iter()
+            .flatten()
+    }
+}
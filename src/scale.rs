@@ -0,0 +1,109 @@
+//! Rescaling the counts of [`Histogram`]s, e.g. to compare corpora of different sizes.
+
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash};
+
+use crate::{Count, Histogram};
+
+impl<K: Hash + Eq, S: BuildHasher + Default, C: Count> Histogram<K, S, C> {
+    /// Multiply every count by `factor`, giving a Histogram with `f64` counts
+    ///
+    /// Categories whose count is not positive afterwards are dropped, so a `factor` of `0.0` or
+    /// less gives an empty Histogram.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("aaab".chars());
+    ///
+    /// let half = hist.scale(0.5);
+    /// assert_eq!(half.count(&'a'), 1.5);
+    /// assert_eq!(half.count(&'b'), 0.5);
+    /// ```
+    #[must_use]
+    pub fn scale(self, factor: f64) -> Histogram<K, S, f64> {
+        let overflow = self.overflow;
+        let map = self
+            .into_iter()
+            .map(|(key, cnt)| (key, cnt.to_f64() * factor))
+            .filter(|&(_, cnt)| cnt > 0.0)
+            .collect();
+        Histogram { map, overflow }
+    }
+
+    /// Scale all counts so that they sum up to `total`
+    ///
+    /// The relative frequencies stay the same. This is useful to compare a large reference corpus
+    /// with a smaller sample, e.g. with [`Histogram::chi_squared()`]. An empty Histogram stays
+    /// empty.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let reference = Histogram::<_>::from_owned_iter("aaab".chars());
+    ///
+    /// let matched = reference.normalize_to(100.0);
+    /// assert_eq!(matched.count(&'a'), 75.0);
+    /// assert_eq!(matched.num_instances(), 100.0);
+    /// ```
+    #[must_use]
+    pub fn normalize_to(self, total: f64) -> Histogram<K, S, f64> {
+        let current = self.instances_f64();
+        if current == 0.0 {
+            return self.scale(0.0);
+        }
+        self.scale(total / current)
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher + Default> Histogram<K, S, f64> {
+    /// Round fractional counts to whole counts, keeping the total as close as possible
+    ///
+    /// Every count is rounded down first, then the counts with the largest remainders are rounded
+    /// up until the total equals the rounded original total. This avoids the drift of rounding
+    /// every count on its own. `key`s whose count ends up as `0` are dropped.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("aaabb".chars());
+    ///
+    /// // 1.8 and 1.2 would both round to 2 on their own
+    /// let downsampled = hist.normalize_to(3.0).round_to_counts();
+    /// assert_eq!(downsampled.count(&'a'), 2);
+    /// assert_eq!(downsampled.count(&'b'), 1);
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn round_to_counts(self) -> Histogram<K, S, usize> {
+        let overflow = self.overflow;
+        let target = self.num_instances().round();
+
+        // Counts are positive, so truncating the rounded down value is exact
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let mut parts: Vec<(K, usize, f64)> = self
+            .into_iter()
+            .map(|(key, cnt)| (key, cnt.floor() as usize, cnt - cnt.floor()))
+            .collect();
+
+        let floored: usize = parts.iter().map(|&(_, cnt, _)| cnt).sum();
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let missing = (target as usize).saturating_sub(floored);
+
+        // NOTE: unstable is okay here, as the map order is already arbitrary
+        parts.sort_unstable_by(|(_, _, a), (_, _, b)| b.total_cmp(a));
+        for (_, cnt, _) in parts.iter_mut().take(missing) {
+            *cnt += 1;
+        }
+
+        Histogram {
+            map: parts
+                .into_iter()
+                .filter(|&(_, cnt, _)| cnt > 0)
+                .map(|(key, cnt, _)| (key, cnt))
+                .collect(),
+            overflow,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Counting Unicode grapheme clusters and words with [`unicode_segmentation`].
+
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{Count, Histogram};
+
+impl<K, S, C> Histogram<K, S, C>
+where
+    K: Hash + Eq + Borrow<str> + for<'a> From<&'a str>,
+    S: BuildHasher,
+    C: Count,
+{
+    /// Count every extended grapheme cluster of `text`
+    ///
+    /// A grapheme cluster is what a reader perceives as a single character. In contrast to
+    /// counting [`char`]s, letters with combining accents, emoji sequences and syllables of
+    /// scripts like Devanagari are counted as a whole.
+    ///
+    /// # Panics
+    /// If the count of a grapheme overflows and the [`OverflowPolicy`](crate::OverflowPolicy) is
+    /// [`OverflowPolicy::Panic`](crate::OverflowPolicy::Panic).
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let mut hist = Histogram::<String>::new();
+    ///
+    /// // An "e" with a combining acute accent, and a plain "e"
+    /// hist.count_graphemes("e\u{301}e");
+    /// assert_eq!(hist.count("e\u{301}"), 1);
+    /// assert_eq!(hist.count("e"), 1);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-segmentation")))]
+    pub fn count_graphemes(&mut self, text: &str) {
+        for grapheme in text.graphemes(true) {
+            self.add_ref(grapheme);
+        }
+    }
+
+    /// Count every word of `text` following the Unicode word boundary rules
+    ///
+    /// In contrast to [`str::split_whitespace()`] punctuation is not part of the words, while
+    /// contractions like "can't" and numbers like "3.14" stay whole.
+    ///
+    /// # Panics
+    /// If the count of a word overflows and the [`OverflowPolicy`](crate::OverflowPolicy) is
+    /// [`OverflowPolicy::Panic`](crate::OverflowPolicy::Panic).
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let mut hist = Histogram::<String>::new();
+    ///
+    /// hist.count_unicode_words("The cat can't jump 3.5 feet, can it? The cat.");
+    /// assert_eq!(hist.count("cat"), 2);
+    /// assert_eq!(hist.count("can't"), 1);
+    /// assert_eq!(hist.count("3.5"), 1);
+    /// assert_eq!(hist.num_instances(), 10);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-segmentation")))]
+    pub fn count_unicode_words(&mut self, text: &str) {
+        for word in text.unicode_words() {
+            self.add_ref(word);
+        }
+    }
+}
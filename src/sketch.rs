@@ -0,0 +1,271 @@
+//! Approximate counting with bounded memory using a Count-Min Sketch.
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+
+use hashbrown::HashMap;
+
+use crate::{Count, DefaultHashBuilder, Histogram};
+
+/// A Count-Min Sketch, approximately counting occurrences of `key`s in a fixed amount of memory
+///
+/// In contrast to a [`Histogram`] the `key`s themselves are not stored, only `depth` rows of
+/// `width` counters. Every `key` maps to one counter per row, and its count is estimated by the
+/// smallest of them. So the estimate is never too low, but can be too high if other `key`s share
+/// the same counters. With a width of `⌈e / epsilon⌉` and a depth of `⌈ln(1 / delta)⌉` the
+/// estimate is at most `epsilon * num_instances()` too high with probability `1 - delta`.
+///
+/// Counters saturate at the maximum of `C` instead of overflowing, as the counts are approximate
+/// anyway.
+///
+/// # Example
+/// ```rust
+/// use histongram::CountMinSketch;
+///
+/// let mut sketch = CountMinSketch::<str>::new(0.001, 0.01);
+///
+/// for url in ["/", "/about", "/", "/contact", "/"] {
+///     sketch.add_ref(url);
+/// }
+///
+/// assert!(sketch.count("/") >= 3);
+/// assert!(sketch.count("/about") >= 1);
+/// ```
+#[derive(Debug)]
+pub struct CountMinSketch<K: ?Sized + Hash, S: BuildHasher = DefaultHashBuilder, C: Count = usize> {
+    width: usize,
+    depth: usize,
+    counters: Box<[C]>,
+    total: C,
+    hash_builder: S,
+    _key: PhantomData<fn(&K)>,
+}
+
+// Not derived, as that would needlessly require `K` to be `Clone`
+impl<K: ?Sized + Hash, S: BuildHasher + Clone, C: Count> Clone for CountMinSketch<K, S, C> {
+    fn clone(&self) -> Self {
+        Self {
+            width: self.width,
+            depth: self.depth,
+            counters: self.counters.clone(),
+            total: self.total,
+            hash_builder: self.hash_builder.clone(),
+            _key: PhantomData,
+        }
+    }
+}
+
+impl<K: ?Sized + Hash, C: Count> CountMinSketch<K, DefaultHashBuilder, C> {
+    /// Create a new empty sketch that overestimates counts by at most `epsilon` times the total
+    /// number of instances, with probability `1 - delta`
+    ///
+    /// # Panics
+    /// If `epsilon` or `delta` are not between `0.0` and `1.0` (exclusive).
+    #[must_use]
+    pub fn new(epsilon: f64, delta: f64) -> Self {
+        Self::with_error_and_hasher(epsilon, delta, DefaultHashBuilder::default())
+    }
+
+    /// Create a new empty sketch with `depth` rows of `width` counters each
+    ///
+    /// # Panics
+    /// If `width` or `depth` is `0`, or `width * depth` overflows `usize`.
+    #[must_use]
+    pub fn with_dimensions(width: usize, depth: usize) -> Self {
+        Self::with_dimensions_and_hasher(width, depth, DefaultHashBuilder::default())
+    }
+}
+
+impl<K: ?Sized + Hash, S: BuildHasher, C: Count> CountMinSketch<K, S, C> {
+    /// Create a new empty sketch like [`CountMinSketch::new()`] using the given `hash_builder`
+    ///
+    /// # Panics
+    /// If `epsilon` or `delta` are not between `0.0` and `1.0` (exclusive).
+    #[must_use]
+    pub fn with_error_and_hasher(epsilon: f64, delta: f64, hash_builder: S) -> Self {
+        assert!(
+            epsilon > 0.0 && epsilon < 1.0,
+            "epsilon must be between 0 and 1, got {epsilon}"
+        );
+        assert!(
+            delta > 0.0 && delta < 1.0,
+            "delta must be between 0 and 1, got {delta}"
+        );
+
+        // Both are small positive numbers after rounding up
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let (width, depth) = (
+            (std::f64::consts::E / epsilon).ceil() as usize,
+            (1.0 / delta).ln().ceil().max(1.0) as usize,
+        );
+        Self::with_dimensions_and_hasher(width, depth, hash_builder)
+    }
+
+    /// Create a new empty sketch like [`CountMinSketch::with_dimensions()`] using the given
+    /// `hash_builder`
+    ///
+    /// # Panics
+    /// If `width` or `depth` is `0`, or `width * depth` overflows `usize`.
+    #[must_use]
+    pub fn with_dimensions_and_hasher(width: usize, depth: usize, hash_builder: S) -> Self {
+        assert!(
+            width > 0 && depth > 0,
+            "a sketch needs at least one counter"
+        );
+        let len = width
+            .checked_mul(depth)
+            .expect("sketch dimensions overflow usize");
+
+        Self {
+            width,
+            depth,
+            counters: vec![C::ZERO; len].into_boxed_slice(),
+            total: C::ZERO,
+            hash_builder,
+            _key: PhantomData,
+        }
+    }
+
+    /// Number of counters per row
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of rows, i.e. of counters per `key`
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Total number of instances added so far
+    ///
+    /// In contrast to [`CountMinSketch::count()`] this is exact.
+    #[must_use]
+    pub fn num_instances(&self) -> C {
+        self.total
+    }
+
+    /// Check if nothing was added so far
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.total.total_cmp(&C::ZERO).is_eq()
+    }
+
+    /// Upper bound of how much [`CountMinSketch::count()`] overestimates, with high probability
+    ///
+    /// This is `e / width` times the number of instances, see [`CountMinSketch`].
+    #[must_use]
+    pub fn error_bound(&self) -> f64 {
+        // Rounding is fine when the numbers get to large to fit f64
+        #[allow(clippy::cast_precision_loss)]
+        let width = self.width as f64;
+        std::f64::consts::E / width * self.total.to_f64()
+    }
+
+    /// Add a single occurrence of `val`
+    pub fn add_ref<Q>(&mut self, val: &Q)
+    where
+        Q: ?Sized + Hash,
+        K: Borrow<Q>,
+    {
+        self.add_n(val, C::ONE);
+    }
+
+    /// Add a single occurrence of `val`
+    ///
+    /// As the `key`s are not stored, this is the same as [`CountMinSketch::add_ref()`].
+    pub fn add_owned(&mut self, val: K)
+    where
+        K: Sized,
+    {
+        self.add_ref(&val);
+    }
+
+    /// Add `n` occurrences of `val`
+    pub fn add_ref_n<Q>(&mut self, val: &Q, n: C)
+    where
+        Q: ?Sized + Hash,
+        K: Borrow<Q>,
+    {
+        self.add_n(val, n);
+    }
+
+    /// Estimated number of times `key` was added, never less than the true count
+    pub fn count<Q>(&self, key: &Q) -> C
+    where
+        Q: ?Sized + Hash,
+        K: Borrow<Q>,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        indices(hash, self.width, self.depth)
+            .map(|idx| self.counters[idx])
+            .min_by(Count::total_cmp)
+            .unwrap_or(C::ZERO)
+    }
+
+    /// Collect the estimated counts of all `candidates` that reach `min_count` into an exact
+    /// [`Histogram`]
+    ///
+    /// As the sketch does not know which `key`s it has seen, the possible heavy hitters have to be
+    /// passed in, for example from a sample of the stream.
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::CountMinSketch;
+    ///
+    /// let mut sketch = CountMinSketch::<String>::with_dimensions(1000, 5);
+    /// for agent in ["curl", "firefox", "firefox", "firefox", "wget"] {
+    ///     sketch.add_ref(agent);
+    /// }
+    ///
+    /// let candidates = ["curl", "firefox", "wget"].map(String::from);
+    /// let heavy = sketch.heavy_hitters(candidates, 2);
+    /// assert_eq!(heavy.num_categories(), 1);
+    /// assert_eq!(heavy.count("firefox"), 3);
+    /// ```
+    pub fn heavy_hitters<I>(&self, candidates: I, min_count: C) -> Histogram<K, S, C>
+    where
+        I: IntoIterator<Item = K>,
+        K: Sized + Eq,
+        S: Clone,
+    {
+        let mut map = HashMap::with_hasher(self.hash_builder.clone());
+        for key in candidates {
+            let cnt = self.count(&key);
+            if cnt.total_cmp(&min_count).is_ge() && cnt.total_cmp(&C::ZERO).is_gt() {
+                map.insert(key, cnt);
+            }
+        }
+        map.into()
+    }
+
+    /// Reset all counters to zero, keeping the dimensions
+    pub fn clear(&mut self) {
+        self.counters.fill(C::ZERO);
+        self.total = C::ZERO;
+    }
+
+    fn add_n<Q: ?Sized + Hash>(&mut self, val: &Q, n: C) {
+        let hash = self.hash_builder.hash_one(val);
+        for idx in indices(hash, self.width, self.depth) {
+            self.counters[idx] = self.counters[idx].saturating_add(n);
+        }
+        self.total = self.total.saturating_add(n);
+    }
+}
+
+/// Index of the counter for `hash` in every row of a `width` by `depth` sketch
+fn indices(hash: u64, width: usize, depth: usize) -> impl Iterator<Item = usize> {
+    // Derive all row hashes from one hash by double hashing, see Kirsch and Mitzenmacher,
+    // "Less Hashing, Same Performance"
+    let (h1, h2) = (hash & 0xFFFF_FFFF, (hash >> 32) | 1);
+    (0..depth).map(move |row| {
+        let combined = h1.wrapping_add((row as u64).wrapping_mul(h2));
+        // The remainder is smaller than `width`, so it fits into a `usize`
+        #[allow(clippy::cast_possible_truncation)]
+        let col = (combined % width as u64) as usize;
+        row * width + col
+    })
+}
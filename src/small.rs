@@ -0,0 +1,278 @@
+//! A Histogram backed by a sorted [`Vec`], for small vocabularies.
+
+use alloc::vec::{self, Vec};
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::hash::{BuildHasher, Hash};
+
+use crate::{Count, Histogram, OverflowPolicy};
+
+/// A Histogram for a handful of `key`s, stored in a [`Vec`] sorted by `key`
+///
+/// This has the same counting API as [`Histogram`], but looks up `key`s by binary search instead
+/// of hashing them. For tiny vocabularies like character classes or part-of-speech tags, that is
+/// faster than hashing and uses less memory. Adding a new `key` takes `O(n)` time, so convert
+/// into a [`Histogram`] with [`SmallHistogram::into_histogram()`] if there are more than a few
+/// dozen `key`s.
+///
+/// # Example
+/// ```rust
+/// use histongram::SmallHistogram;
+///
+/// let mut classes = SmallHistogram::<char>::new();
+/// for c in "Hello, World 42!".chars() {
+///     classes.add_owned(if c.is_alphabetic() { 'a' } else if c.is_numeric() { '0' } else { '.' });
+/// }
+///
+/// assert_eq!(classes.count(&'a'), 10);
+/// assert_eq!(classes.iter().collect::<Vec<_>>(), vec![(&'.', 4), (&'0', 2), (&'a', 10)]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SmallHistogram<K: Ord, C: Count = usize> {
+    /// Sorted by `key`, every `key` occurs at most once
+    entries: Vec<(K, C)>,
+    overflow: OverflowPolicy,
+}
+
+impl<K: Ord, C: Count> Default for SmallHistogram<K, C> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            overflow: OverflowPolicy::Panic,
+        }
+    }
+}
+
+impl<K: Ord> SmallHistogram<K> {
+    /// Create a new empty `SmallHistogram`
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<K: Ord, C: Count> SmallHistogram<K, C> {
+    /// Add a new occurrence of `key`, see [`Histogram::add_ref()`]
+    ///
+    /// # Panics
+    /// If the count of `key` overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    pub fn add_ref<'a, Q>(&mut self, val: &'a Q)
+    where
+        K: Borrow<Q> + From<&'a Q>,
+        Q: ?Sized + Ord,
+    {
+        match self.search(val) {
+            Ok(idx) => self.overflow.add(&mut self.entries[idx].1, C::ONE),
+            Err(idx) => self.entries.insert(idx, (val.into(), C::ONE)),
+        }
+    }
+
+    /// Add a new occurrence of `key`, see [`Histogram::add_owned()`]
+    ///
+    /// # Panics
+    /// If the count of `key` overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    pub fn add_owned(&mut self, val: K) {
+        self.add_owned_n(val, C::ONE);
+    }
+
+    /// Add `n` occurrences of `key`, see [`Histogram::add_owned_n()`]
+    ///
+    /// # Panics
+    /// If the count of `key` overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    pub fn add_owned_n(&mut self, val: K, n: C) {
+        // Only categories that occurred at least once are kept, this also skips NaN
+        if n.partial_cmp(&C::ZERO) != Some(Ordering::Greater) {
+            return;
+        }
+        match self.search(&val) {
+            Ok(idx) => self.overflow.add(&mut self.entries[idx].1, n),
+            Err(idx) => self.entries.insert(idx, (val, n)),
+        }
+    }
+
+    /// Count all owned `key`s of `iter`, see [`Histogram::extend_from_owned()`]
+    ///
+    /// # Panics
+    /// If a count overflows and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    pub fn extend_from_owned<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for val in iter {
+            self.add_owned(val);
+        }
+    }
+
+    /// Set the [`OverflowPolicy`] used when counts get too large for `C`
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.overflow = policy;
+    }
+
+    /// Number of times `key` occurred, see [`Histogram::count()`]
+    pub fn count<Q>(&self, key: &Q) -> C
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.search(key).map_or(C::ZERO, |idx| self.entries[idx].1)
+    }
+
+    /// Get the relative number of times `key` occurred, see [`Histogram::count_rel()`]
+    pub fn count_rel<Q>(&self, key: &Q) -> f64
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        let total = self.instances_f64();
+        if total == 0.0 {
+            return 0.0;
+        }
+        self.count(key).to_f64() / total
+    }
+
+    /// Remove the category `key`, returning its count before, see [`Histogram::remove()`]
+    pub fn remove<Q>(&mut self, key: &Q) -> C
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.search(key)
+            .map_or(C::ZERO, |idx| self.entries.remove(idx).1)
+    }
+
+    /// Only keep the categories for which `keep` returns `true`, see [`Histogram::retain()`]
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&K, C) -> bool,
+    {
+        self.entries.retain(|(key, cnt)| keep(key, *cnt));
+    }
+
+    /// Number of different `key`s
+    #[must_use]
+    pub fn num_categories(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Sum of all counts
+    ///
+    /// # Panics
+    /// If the total overflows `C` and the [`OverflowPolicy`] is [`OverflowPolicy::Panic`].
+    #[must_use]
+    pub fn num_instances(&self) -> C {
+        self.overflow.sum(self.entries.iter().map(|&(_, cnt)| cnt))
+    }
+
+    /// Check if nothing was added so far
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over all `key`s and their counts, sorted ascending by `key`
+    #[must_use]
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, C)> + ExactSizeIterator {
+        self.entries.iter().map(|(key, cnt)| (key, *cnt))
+    }
+
+    /// Iterate over all `key`s and their relative counts, sorted ascending by `key`, see
+    /// [`Histogram::iter_rel()`]
+    #[must_use]
+    pub fn iter_rel(&self) -> impl DoubleEndedIterator<Item = (&K, f64)> + ExactSizeIterator {
+        let total = self.instances_f64();
+        self.iter()
+            .map(move |(key, cnt)| (key, cnt.to_f64() / total))
+    }
+
+    /// Get the `k` most common `key`s and their counts sorted descending by count, see
+    /// [`Histogram::top_k()`]
+    ///
+    /// In contrast to [`Histogram`], `key`s with the same count are sorted ascending by `key`.
+    #[must_use]
+    pub fn top_k(&self, k: usize) -> Vec<(&K, C)> {
+        let mut top: Vec<_> = self.iter().collect();
+        // Stable, so `key`s with the same count stay sorted by `key`
+        top.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        top.truncate(k);
+        top
+    }
+
+    /// Get a vector of `key`s and counts sorted descending by count, see
+    /// [`Histogram::sorted_occurrences()`]
+    ///
+    /// In contrast to [`Histogram`], `key`s with the same count are sorted ascending by `key`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::SmallHistogram;
+    /// let mut hist = SmallHistogram::new();
+    /// hist.extend_from_owned("xaxb".chars());
+    ///
+    /// assert_eq!(hist.sorted_occurrences(), vec![('x', 2), ('a', 1), ('b', 1)]);
+    /// ```
+    #[must_use]
+    pub fn sorted_occurrences(self) -> Vec<(K, C)> {
+        let mut sorted = self.entries;
+        // Stable, so `key`s with the same count stay sorted by `key`
+        sorted.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        sorted
+    }
+
+    /// Convert into a hash based [`Histogram`], e.g. when there are too many `key`s
+    #[must_use]
+    pub fn into_histogram<S>(self) -> Histogram<K, S, C>
+    where
+        K: Hash,
+        S: BuildHasher + Default,
+    {
+        let mut hist: Histogram<K, S, C> = self.entries.into_iter().collect();
+        hist.set_overflow_policy(self.overflow);
+        hist
+    }
+
+    /// Sum of all counts as `f64`, which can not overflow
+    fn instances_f64(&self) -> f64 {
+        self.entries.iter().map(|(_, cnt)| cnt.to_f64()).sum()
+    }
+
+    fn search<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+    {
+        self.entries
+            .binary_search_by(|(probe, _)| probe.borrow().cmp(key))
+    }
+}
+
+impl<K: Ord, C: Count> IntoIterator for SmallHistogram<K, C> {
+    type Item = (K, C);
+    type IntoIter = vec::IntoIter<(K, C)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a, K, C, Q> Extend<&'a Q> for SmallHistogram<K, C>
+where
+    K: Ord + Borrow<Q> + From<&'a Q>,
+    Q: ?Sized + Ord + 'a,
+    C: Count,
+{
+    fn extend<T: IntoIterator<Item = &'a Q>>(&mut self, iter: T) {
+        for item in iter {
+            self.add_ref(item);
+        }
+    }
+}
+
+impl<'a, K, C, Q> FromIterator<&'a Q> for SmallHistogram<K, C>
+where
+    K: Ord + Borrow<Q> + From<&'a Q>,
+    Q: ?Sized + Ord + 'a,
+    C: Count,
+{
+    fn from_iter<T: IntoIterator<Item = &'a Q>>(iter: T) -> Self {
+        let mut hist = Self::default();
+        hist.extend(iter);
+        hist
+    }
+}
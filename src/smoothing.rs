@@ -0,0 +1,354 @@
+//! Smoothed probability estimates from a [`Histogram`], giving unseen `key`s non-zero
+//! probability.
+
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::hash::{BuildHasher, Hash};
+
+use crate::{Count, Histogram};
+
+/// Add-k smoothed view of a [`Histogram`], created by [`Histogram::smoothed()`]
+///
+/// Every `key` of the vocabulary gets `k` pseudo-counts added to its count, so the probability
+/// of a `key` is `(count + k) / (num_instances + k * vocab_size)`.
+///
+/// # Example
+/// ```rust
+/// # use histongram::Histogram;
+/// let hist = Histogram::<_>::from_owned_iter("aab".chars());
+/// let smoothed = hist.smoothed(1.0, 4);
+///
+/// assert_eq!(smoothed.prob(&'a'), 3.0 / 7.0);
+/// assert_eq!(smoothed.prob(&'z'), 1.0 / 7.0);
+/// ```
+#[derive(Debug)]
+pub struct Smoothed<'a, K: Hash + Eq, S: BuildHasher, C: Count> {
+    hist: &'a Histogram<K, S, C>,
+    k: f64,
+    denominator: f64,
+}
+
+// Not derived, as that would needlessly require `K`, `S` and `C` to be `Copy`
+impl<K: Hash + Eq, S: BuildHasher, C: Count> Clone for Smoothed<'_, K, S, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher, C: Count> Copy for Smoothed<'_, K, S, C> {}
+
+impl<'a, K: Hash + Eq, S: BuildHasher, C: Count> Smoothed<'a, K, S, C> {
+    /// Smoothed probability of `key`
+    ///
+    /// This is also non-zero for `key`s that never occurred. It is `0.0` if there is nothing to
+    /// smooth, i.e. for an empty Histogram with `k == 0.0`.
+    #[must_use]
+    pub fn prob<Q>(&self, key: &Q) -> f64
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        if self.denominator == 0.0 {
+            return 0.0;
+        }
+        (self.hist.count(key).to_f64() + self.k) / self.denominator
+    }
+
+    /// Natural logarithm of the smoothed probability of `key`
+    ///
+    /// Summing these is the usual way of scoring a sequence without running into underflow.
+    #[must_use]
+    pub fn log_prob<Q>(&self, key: &Q) -> f64
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.prob(key).ln()
+    }
+
+    /// Smoothed probability of any single `key` that never occurred
+    #[must_use]
+    pub fn unseen_prob(&self) -> f64 {
+        if self.denominator == 0.0 {
+            return 0.0;
+        }
+        self.k / self.denominator
+    }
+
+    /// The underlying Histogram
+    #[must_use]
+    pub fn histogram(&self) -> &'a Histogram<K, S, C> {
+        self.hist
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher, C: Count> Histogram<K, S, C> {
+    /// Add-k smoothed probabilities over a vocabulary of `vocab_size` `key`s
+    ///
+    /// `vocab_size` is the number of distinct `key`s that could occur, including the ones seen so
+    /// far. If it is smaller than [`Histogram::num_categories()`], the number of categories is
+    /// used instead, so the probabilities still sum up to at most `1.0`. A `k` of `1.0` is
+    /// Laplace smoothing, `0.0` gives the plain relative frequencies.
+    ///
+    /// # Panics
+    /// If `k` is negative or NaN.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("aab".chars());
+    /// let smoothed = hist.smoothed(1.0, 5);
+    ///
+    /// assert_eq!(smoothed.prob(&'b'), 0.25);
+    /// assert_eq!(smoothed.unseen_prob(), 0.125);
+    /// ```
+    #[must_use]
+    pub fn smoothed(&self, k: f64, vocab_size: usize) -> Smoothed<'_, K, S, C> {
+        assert!(k >= 0.0, "smoothing constant must not be negative, got {k}");
+
+        // Rounding is fine when the numbers get to large to fit f64
+        #[allow(clippy::cast_precision_loss)]
+        let vocab_size = vocab_size.max(self.num_categories()) as f64;
+        Smoothed {
+            hist: self,
+            k,
+            denominator: self.instances_f64() + k * vocab_size,
+        }
+    }
+}
+
+/// Simple Good–Turing estimate of the probabilities of a [`Histogram`], created by
+/// [`Histogram::good_turing()`]
+///
+/// Good–Turing discounts the count of every `key` based on how many `key`s share that count, and
+/// reserves the freed probability mass for `key`s that never occurred. This follows the "Simple
+/// Good–Turing" method by Gale and Sampson, which smooths the count-of-counts with a log-log
+/// linear fit before using them.
+///
+/// # Example
+/// ```rust
+/// # use histongram::Histogram;
+/// let hist = Histogram::<_>::from_owned_iter("aaaabbbccd".chars());
+/// let gt = hist.good_turing().unwrap();
+///
+/// // One instance out of ten was a key seen only once
+/// assert_eq!(gt.unseen_mass(), 0.1);
+/// assert!(gt.prob(&'a') < 0.4);
+/// assert_eq!(gt.prob(&'z'), 0.0);
+/// ```
+#[derive(Debug)]
+pub struct GoodTuring<'a, K: Hash + Eq, S: BuildHasher, C: Count> {
+    hist: &'a Histogram<K, S, C>,
+    /// Adjusted count `r*` and probability of a single `key` for every observed count `r`
+    adjusted: BTreeMap<C, (f64, f64)>,
+    unseen: f64,
+}
+
+// Not derived, as that would needlessly require `K` and `S` to be `Clone`
+impl<K: Hash + Eq, S: BuildHasher, C: Count> Clone for GoodTuring<'_, K, S, C> {
+    fn clone(&self) -> Self {
+        Self {
+            hist: self.hist,
+            adjusted: self.adjusted.clone(),
+            unseen: self.unseen,
+        }
+    }
+}
+
+impl<'a, K: Hash + Eq, S: BuildHasher, C: Count + Ord> GoodTuring<'a, K, S, C> {
+    /// Estimated probability of `key`
+    ///
+    /// `key`s that never occurred have a probability of `0.0`, the mass reserved for all of them
+    /// together is [`GoodTuring::unseen_mass()`].
+    #[must_use]
+    pub fn prob<Q>(&self, key: &Q) -> f64
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.adjusted
+            .get(&self.hist.count(key))
+            .map_or(0.0, |&(_count, prob)| prob)
+    }
+
+    /// Adjusted count `r*` of `key`, which is `0.0` for `key`s that never occurred
+    #[must_use]
+    pub fn adjusted_count<Q>(&self, key: &Q) -> f64
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.adjusted
+            .get(&self.hist.count(key))
+            .map_or(0.0, |&(count, _prob)| count)
+    }
+
+    /// Total probability of all `key`s that never occurred
+    ///
+    /// This is the fraction of instances that belong to hapax legomena, see
+    /// [`Histogram::hapax_count()`].
+    #[must_use]
+    pub fn unseen_mass(&self) -> f64 {
+        self.unseen
+    }
+
+    /// Probability of a single unseen `key`, if there are `num_unseen` possible `key`s that never
+    /// occurred
+    ///
+    /// The unseen mass is split evenly between them. This is `0.0` for `num_unseen == 0`.
+    #[must_use]
+    pub fn unseen_prob(&self, num_unseen: usize) -> f64 {
+        if num_unseen == 0 {
+            return 0.0;
+        }
+        // Rounding is fine when the numbers get to large to fit f64
+        #[allow(clippy::cast_precision_loss)]
+        let num_unseen = num_unseen as f64;
+        self.unseen / num_unseen
+    }
+
+    /// The underlying Histogram
+    #[must_use]
+    pub fn histogram(&self) -> &'a Histogram<K, S, C> {
+        self.hist
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher, C: Count + Ord> Histogram<K, S, C> {
+    /// Count-of-counts spectrum: how many `key`s occurred exactly `r` times, for every `r`
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let hist = Histogram::<_>::from_owned_iter("aaabbcd".chars());
+    /// assert_eq!(hist.count_spectrum(), BTreeMap::from([(1, 2), (2, 1), (3, 1)]));
+    /// ```
+    #[must_use]
+    pub fn count_spectrum(&self) -> BTreeMap<C, usize> {
+        let mut spectrum = BTreeMap::new();
+        for (_key, cnt) in self.iter() {
+            *spectrum.entry(cnt).or_insert(0) += 1;
+        }
+        spectrum
+    }
+
+    /// Simple Good–Turing estimate of the probabilities, see [`GoodTuring`]
+    ///
+    /// Returns `None` for an empty Histogram, as there is nothing to estimate from.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("aaaabbbccd".chars());
+    /// let gt = hist.good_turing().unwrap();
+    ///
+    /// let seen: f64 = hist.iter().map(|(key, _cnt)| gt.prob(key)).sum();
+    /// assert!((seen + gt.unseen_mass() - 1.0).abs() < 1e-9);
+    /// ```
+    #[must_use]
+    pub fn good_turing(&self) -> Option<GoodTuring<'_, K, S, C>> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let counts: Vec<(C, usize)> = self.count_spectrum().into_iter().collect();
+        // Rounding is fine when the numbers get to large to fit f64
+        #[allow(clippy::cast_precision_loss)]
+        let spectrum: Vec<(f64, f64)> = counts
+            .iter()
+            .map(|&(r, n_r)| (r.to_f64(), n_r as f64))
+            .collect();
+        let total = self.instances_f64();
+        let n_1 = counts
+            .iter()
+            .zip(&spectrum)
+            .find(|((r, _), _)| *r == C::ONE)
+            .map_or(0.0, |(_, &(_r, n_r))| n_r);
+        let unseen = n_1 / total;
+
+        let slope = spectrum_slope(&spectrum);
+        let smoothed = |r: f64| r * (1.0 + 1.0 / r).powf(slope + 1.0);
+
+        // Use the plain Turing estimate as long as it differs significantly from the smoothed
+        // one, after that stick to the smoothed estimate
+        let mut use_turing = true;
+        let mut adjusted: Vec<f64> = Vec::with_capacity(spectrum.len());
+        for (idx, &(r, n_r)) in spectrum.iter().enumerate() {
+            let y = smoothed(r);
+            let has_next = counts
+                .get(idx + 1)
+                .is_some_and(|&(next_r, _)| next_r == counts[idx].0 + C::ONE);
+            match spectrum.get(idx + 1) {
+                Some(&(_, n_next)) if use_turing && has_next => {
+                    let x = (r + 1.0) * n_next / n_r;
+                    let sd = (r + 1.0) * (n_next / (n_r * n_r) * (1.0 + n_next / n_r)).sqrt();
+                    if (x - y).abs() > 1.96 * sd {
+                        adjusted.push(x);
+                    } else {
+                        use_turing = false;
+                        adjusted.push(y);
+                    }
+                }
+                _ => {
+                    use_turing = false;
+                    adjusted.push(y);
+                }
+            }
+        }
+
+        // Renormalize, so the seen keys share exactly the mass not reserved for unseen ones
+        let adjusted_total: f64 = spectrum
+            .iter()
+            .zip(&adjusted)
+            .map(|(&(_r, n_r), r_star)| n_r * r_star)
+            .sum();
+        let adjusted = counts
+            .into_iter()
+            .zip(adjusted)
+            .map(|((r, _n_r), r_star)| (r, (r_star, (1.0 - unseen) * r_star / adjusted_total)))
+            .collect();
+
+        Some(GoodTuring {
+            hist: self,
+            adjusted,
+            unseen,
+        })
+    }
+}
+
+/// Slope of the log-log regression of the averaged count-of-counts `Z_r` on `r`
+///
+/// Falls back to `-1.0` if there are not enough distinct counts to fit a line, which makes the
+/// smoothed estimate keep the counts unchanged.
+fn spectrum_slope(spectrum: &[(f64, f64)]) -> f64 {
+    if spectrum.len() < 2 {
+        return -1.0;
+    }
+
+    // Average `n_r` over the gap to the neighbouring counts, as most large `r` have `n_r == 0`
+    let points: Vec<(f64, f64)> = spectrum
+        .iter()
+        .enumerate()
+        .map(|(idx, &(r, n_r))| {
+            let prev = if idx == 0 { 0.0 } else { spectrum[idx - 1].0 };
+            let next = spectrum.get(idx + 1).map_or(2.0 * r - prev, |&(r, _)| r);
+            (r.ln(), (2.0 * n_r / (next - prev)).ln())
+        })
+        .collect();
+
+    // Rounding is fine when the numbers get to large to fit f64
+    #[allow(clippy::cast_precision_loss)]
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let mut cov = 0.0;
+    let mut var_x = 0.0;
+    for (x, y) in &points {
+        cov += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x).powi(2);
+    }
+    cov / var_x
+}
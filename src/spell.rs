@@ -0,0 +1,198 @@
+//! Spelling correction based on the word counts of a [`Histogram`].
+
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+use crate::{Count, Histogram};
+
+/// Suggests corrections for misspelled words from the known words of a Histogram
+///
+/// This follows Peter Norvig's classic spelling corrector: all strings within an edit distance of
+/// one or two of a word are generated, and the known word that occurred most often wins. Known
+/// words are always considered correct. The quality mainly depends on the counted corpus, which
+/// should be large and from the same domain as the words to correct.
+///
+/// Candidate corrections are made of the characters that occur in the known words, so this
+/// works for any alphabet. For a word of length `n` and an alphabet of `a` characters there are
+/// about `2 · n · a` candidates of edit distance one and the square of that of edit distance two,
+/// which are only generated if no known word is closer. Corpora with many distinct characters,
+/// like Chinese text or text with stray symbols, make this slow, so restrict the alphabet with
+/// [`SpellChecker::with_alphabet()`] in that case.
+///
+/// # Example
+/// ```rust
+/// use histongram::{Histogram, SpellChecker};
+///
+/// let words: Histogram<String> = "the cat sat on the mat with the other cat"
+///     .split(' ')
+///     .collect();
+/// let checker = SpellChecker::new(&words);
+///
+/// assert_eq!(checker.correct("teh").map(String::as_str), Some("the"));
+/// assert_eq!(checker.correct("cta").map(String::as_str), Some("cat"));
+/// assert_eq!(checker.correct("mat").map(String::as_str), Some("mat"));
+/// assert_eq!(checker.correct("xylophone"), None);
+/// ```
+#[derive(Debug)]
+pub struct SpellChecker<'a, K: Hash + Eq, S: BuildHasher, C: Count> {
+    words: &'a Histogram<K, S, C>,
+    /// Characters to insert and replace when generating candidates, sorted
+    alphabet: Vec<char>,
+}
+
+// Not derived, as that would needlessly require `K`, `S` and `C` to be `Clone`
+impl<K: Hash + Eq, S: BuildHasher, C: Count> Clone for SpellChecker<'_, K, S, C> {
+    fn clone(&self) -> Self {
+        Self {
+            words: self.words,
+            alphabet: self.alphabet.clone(),
+        }
+    }
+}
+
+impl<'a, K, S, C> SpellChecker<'a, K, S, C>
+where
+    K: Hash + Eq + Ord + Borrow<str>,
+    S: BuildHasher,
+    C: Count,
+{
+    /// Create a spell checker using the `key`s of `words` as the known words, weighted by their
+    /// counts
+    ///
+    /// All characters of the known words make up the alphabet of candidate corrections, see
+    /// [`SpellChecker::with_alphabet()`] for a smaller one.
+    #[must_use]
+    pub fn new(words: &'a Histogram<K, S, C>) -> Self {
+        Self::with_alphabet(words, words.keys().flat_map(|word| word.borrow().chars()))
+    }
+
+    /// Create a spell checker like [`SpellChecker::new()`], but only insert and replace the
+    /// characters of `alphabet` when generating candidate corrections
+    ///
+    /// The number of candidates of edit distance two grows with the square of the alphabet size,
+    /// so a small alphabet keeps corrections fast. Known words with other characters can still be
+    /// suggested, as long as those are not the ones that have to be inserted or replaced.
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::{Histogram, SpellChecker};
+    ///
+    /// let words: Histogram<String> = ["café", "cafe", "cafe"].into_iter().collect();
+    /// let checker = SpellChecker::with_alphabet(&words, 'a'..='z');
+    ///
+    /// assert_eq!(checker.correct("caffe").map(String::as_str), Some("cafe"));
+    /// assert_eq!(checker.correct("caféé").map(String::as_str), Some("café"));
+    /// ```
+    #[must_use]
+    pub fn with_alphabet<I>(words: &'a Histogram<K, S, C>, alphabet: I) -> Self
+    where
+        I: IntoIterator<Item = char>,
+    {
+        let alphabet: BTreeSet<char> = alphabet.into_iter().collect();
+        Self {
+            words,
+            alphabet: alphabet.into_iter().collect(),
+        }
+    }
+
+    /// The most likely correction of `word`, or `None` if there is no known word within an edit
+    /// distance of two
+    ///
+    /// Known words are returned as they are. Otherwise the most common known word with the
+    /// smallest edit distance wins, ties are broken by choosing the smallest word.
+    #[must_use]
+    pub fn correct(&self, word: &str) -> Option<&'a K> {
+        self.suggestions(word, 1)
+            .into_iter()
+            .next()
+            .map(|(known, _cnt)| known)
+    }
+
+    /// Up to `max` known words with the smallest edit distance to `word`, together with their
+    /// counts, sorted descending by count
+    ///
+    /// Only the candidates of the smallest edit distance that has any known words are returned,
+    /// so a known `word` only suggests itself.
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::{Histogram, SpellChecker};
+    ///
+    /// let words: Histogram<String> = ["cat", "cat", "cap", "car", "cart"].into_iter().collect();
+    /// let checker = SpellChecker::new(&words);
+    ///
+    /// let suggestions: Vec<_> = checker
+    ///     .suggestions("caz", 2)
+    ///     .into_iter()
+    ///     .map(|(word, cnt)| (word.as_str(), cnt))
+    ///     .collect();
+    /// assert_eq!(suggestions, [("cat", 2), ("cap", 1)]);
+    /// ```
+    #[must_use]
+    pub fn suggestions(&self, word: &str, max: usize) -> Vec<(&'a K, C)> {
+        if let Some(known) = self.known([word]) {
+            return known.into_iter().take(max).collect();
+        }
+
+        let edits = self.edits(word);
+        if let Some(known) = self.known(edits.iter().map(String::as_str)) {
+            return known.into_iter().take(max).collect();
+        }
+
+        let edits2 = edits.iter().flat_map(|edit| self.edits(edit));
+        self.known(edits2)
+            .map(|known| known.into_iter().take(max).collect())
+            .unwrap_or_default()
+    }
+
+    /// The known words among `candidates`, sorted descending by count, or `None` if there are none
+    fn known<Q>(&self, candidates: impl IntoIterator<Item = Q>) -> Option<Vec<(&'a K, C)>>
+    where
+        Q: Borrow<str>,
+    {
+        let words = self.words;
+        let mut known: Vec<_> = candidates
+            .into_iter()
+            .filter_map(|candidate| words.map.get_key_value(candidate.borrow()))
+            .map(|(word, &cnt)| (word, cnt))
+            .collect();
+        if known.is_empty() {
+            return None;
+        }
+
+        known.sort_unstable_by(|(ka, a), (kb, b)| b.total_cmp(a).then_with(|| ka.cmp(kb)));
+        known.dedup_by(|(ka, _), (kb, _)| ka == kb);
+        Some(known)
+    }
+
+    /// All strings one deletion, transposition, replacement or insertion away from `word`
+    fn edits(&self, word: &str) -> Vec<String> {
+        let chars: Vec<char> = word.chars().collect();
+        let mut edits = Vec::new();
+
+        for split in 0..=chars.len() {
+            let (left, right) = chars.split_at(split);
+            let with = |middle: &[char], rest: &[char]| -> String {
+                left.iter().chain(middle).chain(rest).collect()
+            };
+
+            if let Some((_, rest)) = right.split_first() {
+                edits.push(with(&[], rest));
+                for &c in &self.alphabet {
+                    edits.push(with(&[c], rest));
+                }
+            }
+            if let [first, second, rest @ ..] = right {
+                edits.push(with(&[*second, *first], rest));
+            }
+            for &c in &self.alphabet {
+                edits.push(with(&[c], right));
+            }
+        }
+
+        edits
+    }
+}
@@ -0,0 +1,349 @@
+//! Statistics on the counts of [`Histogram`]s
+//!
+//! The statistical tests check whether two histograms could have been drawn from the same
+//! distribution. They are the basis of keyness analysis, i.e. finding which `key`s are
+//! over-represented in one corpus compared to another.
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+
+use crate::compare::union_counts;
+use crate::{Count, Histogram};
+
+/// Result of a statistical test like [`Histogram::chi_squared()`] or [`Histogram::g_test()`]
+///
+/// The `statistic` can be compared against the critical value of the chi-squared distribution with
+/// `degrees_of_freedom` degrees of freedom to decide about significance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestStatistic {
+    /// Value of the test statistic
+    pub statistic: f64,
+    /// Degrees of freedom of the test
+    pub degrees_of_freedom: usize,
+}
+
+/// Result of fitting Zipf's law to a [`Histogram`], see [`Histogram::zipf_fit()`]
+///
+/// Zipf's law states that the count of the `key` with rank `r` is proportional to `1 / r^s`, so
+/// `count(r) ≈ scale / r^exponent`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZipfFit {
+    /// The estimated exponent `s`, which is close to `1.0` for natural language
+    pub exponent: f64,
+    /// The estimated count of the most common `key`
+    pub scale: f64,
+    /// Coefficient of determination (R²) of the fit in log-log space
+    ///
+    /// A value close to `1.0` means the counts follow Zipf's law closely.
+    pub r_squared: f64,
+}
+
+/// A confidence interval of a relative frequency, see [`Histogram::count_rel_interval()`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfidenceInterval {
+    /// Lower bound of the interval, at least `0.0`
+    pub lower: f64,
+    /// Upper bound of the interval, at most `1.0`
+    pub upper: f64,
+}
+
+impl ConfidenceInterval {
+    /// Check if the relative frequency `p` lies within the interval
+    #[must_use]
+    pub fn contains(self, p: f64) -> bool {
+        self.lower <= p && p <= self.upper
+    }
+
+    /// Check if both intervals have any relative frequency in common
+    ///
+    /// If the intervals of a `key` in two corpora do not overlap, the difference between its
+    /// frequencies is unlikely to be caused by chance.
+    #[must_use]
+    pub fn overlaps(self, other: Self) -> bool {
+        self.lower <= other.upper && other.lower <= self.upper
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher, C: Count> Histogram<K, S, C> {
+    /// Fit Zipf's law to the rank-frequency distribution of this Histogram
+    ///
+    /// The fit is a least squares linear regression of `ln(count)` on `ln(rank)`. Returns `None`
+    /// if there are less than two categories, as there is nothing to fit then.
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::Histogram;
+    ///
+    /// // Counts that follow Zipf's law with an exponent of 1 exactly
+    /// let hist = Histogram::<_>::from_counts([("a", 60), ("b", 30), ("c", 20), ("d", 15)]);
+    ///
+    /// let fit = hist.zipf_fit().unwrap();
+    /// assert!((fit.exponent - 1.0).abs() < 1e-9);
+    /// assert!((fit.scale - 60.0).abs() < 1e-9);
+    /// assert!((fit.r_squared - 1.0).abs() < 1e-9);
+    /// ```
+    #[must_use]
+    pub fn zipf_fit(&self) -> Option<ZipfFit> {
+        if self.num_categories() < 2 {
+            return None;
+        }
+
+        let mut counts: Vec<_> = self.iter().map(|(_key, cnt)| cnt).collect();
+        counts.sort_unstable_by(|a, b| b.total_cmp(a));
+
+        // Rounding is fine when the numbers get to large to fit f64
+        #[allow(clippy::cast_precision_loss)]
+        let points: Vec<(f64, f64)> = counts
+            .iter()
+            .enumerate()
+            .map(|(idx, cnt)| (((idx + 1) as f64).ln(), cnt.to_f64().ln()))
+            .collect();
+
+        #[allow(clippy::cast_precision_loss)]
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+        let mut cov = 0.0;
+        let mut var_x = 0.0;
+        let mut var_y = 0.0;
+        for (x, y) in &points {
+            cov += (x - mean_x) * (y - mean_y);
+            var_x += (x - mean_x).powi(2);
+            var_y += (y - mean_y).powi(2);
+        }
+
+        let slope = cov / var_x;
+        let intercept = mean_y - slope * mean_x;
+        let r_squared = if var_y == 0.0 {
+            // All counts are equal, so the horizontal line fits perfectly
+            1.0
+        } else {
+            cov * cov / (var_x * var_y)
+        };
+
+        Some(ZipfFit {
+            exponent: -slope,
+            scale: intercept.exp(),
+            r_squared,
+        })
+    }
+
+    /// The count at the `p`-th percentile of all categories, or `None` for an empty Histogram
+    ///
+    /// This uses the nearest rank method on the counts sorted ascending, so `p = 0.5` is the count
+    /// of the median category. A `p` of `0.0` or less gives the lowest count, a `p` of `1.0` or
+    /// more the highest one.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("aaaxxzzzzz".chars());
+    ///
+    /// assert_eq!(hist.count_percentile(0.0), Some(2));
+    /// assert_eq!(hist.count_percentile(0.5), Some(3));
+    /// assert_eq!(hist.count_percentile(1.0), Some(5));
+    /// ```
+    #[must_use]
+    pub fn count_percentile(&self, p: f64) -> Option<C> {
+        let mut counts: Vec<_> = self.counts().collect();
+        counts.sort_unstable_by(C::total_cmp);
+
+        // Rounding is fine when the numbers get to large to fit f64
+        #[allow(
+            clippy::cast_precision_loss,
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss
+        )]
+        let rank = (p * counts.len() as f64).ceil() as usize;
+        let idx = rank.saturating_sub(1).min(counts.len().checked_sub(1)?);
+        Some(counts[idx])
+    }
+
+    /// The count of the category the `p`-th percentile of all instances falls into, or `None` for
+    /// an empty Histogram
+    ///
+    /// In contrast to [`Histogram::count_percentile()`] every category is weighted by its count.
+    /// So the fraction `p` of all instances are in categories with at most this count. This is
+    /// handy to pick a threshold for [`Histogram::prune_below()`] that keeps most instances.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let hist = Histogram::<_>::from_owned_iter("aaaxxzzzzz".chars());
+    ///
+    /// assert_eq!(hist.instances_percentile(0.2), Some(2));
+    /// assert_eq!(hist.instances_percentile(0.5), Some(3));
+    /// assert_eq!(hist.instances_percentile(0.9), Some(5));
+    /// ```
+    #[must_use]
+    pub fn instances_percentile(&self, p: f64) -> Option<C> {
+        let mut counts: Vec<_> = self.counts().collect();
+        counts.sort_unstable_by(C::total_cmp);
+
+        let threshold = p * self.instances_f64();
+        let mut seen = 0.0;
+        counts
+            .iter()
+            .copied()
+            .find(|&cnt| {
+                seen += cnt.to_f64();
+                seen >= threshold
+            })
+            .or_else(|| counts.last().copied())
+    }
+
+    /// Wilson score confidence interval of the relative frequency of `key`, or `None` for an
+    /// empty Histogram
+    ///
+    /// `z` is the quantile of the standard normal distribution for the desired confidence level,
+    /// e.g. `1.96` for 95% or `2.576` for 99%. In contrast to the normal approximation the Wilson
+    /// interval stays within `0.0..=1.0` and works well for rare `key`s and small corpora. Absent
+    /// `key`s get an interval starting at `0.0`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let small = Histogram::<_>::from_counts([("a", 3), ("b", 7)]);
+    /// let large = Histogram::<_>::from_counts([("a", 300), ("b", 700)]);
+    ///
+    /// let in_small = small.count_rel_interval("a", 1.96).unwrap();
+    /// let in_large = large.count_rel_interval("a", 1.96).unwrap();
+    /// assert!(in_small.contains(0.3) && in_large.contains(0.3));
+    /// assert!(in_small.upper - in_small.lower > in_large.upper - in_large.lower);
+    ///
+    /// let other = Histogram::<_>::from_counts([("a", 450), ("b", 550)]);
+    /// assert!(!in_large.overlaps(other.count_rel_interval("a", 1.96).unwrap()));
+    /// ```
+    pub fn count_rel_interval<Q>(&self, key: &Q, z: f64) -> Option<ConfidenceInterval>
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        let total = self.instances_f64();
+        if total == 0.0 {
+            return None;
+        }
+        Some(wilson_interval(self.count(key).to_f64(), total, z))
+    }
+
+    /// Iterate over all `key`s together with the Wilson score confidence interval of their
+    /// relative frequency, see [`Histogram::count_rel_interval()`]
+    ///
+    /// The order of `key`s is arbitrary.
+    pub fn iter_rel_intervals(&self, z: f64) -> impl Iterator<Item = (&K, ConfidenceInterval)> {
+        let total = self.instances_f64();
+        self.iter()
+            .map(move |(key, cnt)| (key, wilson_interval(cnt.to_f64(), total, z)))
+    }
+
+    /// Pearson's chi-squared test of homogeneity between `self` and `other`
+    ///
+    /// The two histograms form a contingency table with one row per `key` of either histogram and
+    /// one column per histogram. The statistic is `0.0` if both have the same relative
+    /// frequencies, and grows with the difference between them. If either histogram is empty there
+    /// is nothing to compare and the statistic as well as the degrees of freedom are `0`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let a = Histogram::<_>::from_owned_iter("aabb".chars());
+    /// let b = Histogram::<_>::from_owned_iter("aaabbb".chars());
+    /// let c = Histogram::<_>::from_owned_iter("aaaaab".chars());
+    ///
+    /// assert_eq!(a.chi_squared(&b).statistic, 0.0);
+    /// assert!(a.chi_squared(&c).statistic > 0.0);
+    /// assert_eq!(a.chi_squared(&c).degrees_of_freedom, 1);
+    /// ```
+    #[must_use]
+    pub fn chi_squared<S2: BuildHasher>(&self, other: &Histogram<K, S2, C>) -> TestStatistic {
+        contingency_test(self, other, |observed, expected| {
+            (observed - expected).powi(2) / expected
+        })
+    }
+
+    /// Log-likelihood ratio test (G-test) of homogeneity between `self` and `other`
+    ///
+    /// This is an alternative to [`Histogram::chi_squared()`] using the same contingency table. It
+    /// is often preferred for text, as it behaves better for rare `key`s.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use histongram::Histogram;
+    /// let a = Histogram::<_>::from_owned_iter("aabb".chars());
+    /// let b = Histogram::<_>::from_owned_iter("aaaaab".chars());
+    ///
+    /// let g = a.g_test(&b);
+    /// assert!(g.statistic > 0.0);
+    /// assert_eq!(g.degrees_of_freedom, 1);
+    /// ```
+    #[must_use]
+    pub fn g_test<S2: BuildHasher>(&self, other: &Histogram<K, S2, C>) -> TestStatistic {
+        let mut result = contingency_test(self, other, |observed, expected| {
+            // An empty cell contributes nothing, as `x * ln(x)` tends to 0
+            if observed == 0.0 {
+                0.0
+            } else {
+                observed * (observed / expected).ln()
+            }
+        });
+        result.statistic *= 2.0;
+        result
+    }
+}
+
+/// Wilson score interval for `successes` out of `total` trials with the normal quantile `z`
+fn wilson_interval(successes: f64, total: f64, z: f64) -> ConfidenceInterval {
+    let p = successes / total;
+    let z2 = z * z;
+    let denominator = 1.0 + z2 / total;
+    let center = (p + z2 / (2.0 * total)) / denominator;
+    let half_width = z / denominator * (p * (1.0 - p) / total + z2 / (4.0 * total * total)).sqrt();
+
+    ConfidenceInterval {
+        // Rounding errors could make this slightly leave the valid range
+        lower: (center - half_width).max(0.0),
+        upper: (center + half_width).min(1.0),
+    }
+}
+
+/// Sum `cell(observed, expected)` over the cells of the `key`s × 2 contingency table
+fn contingency_test<K, S1, S2, C>(
+    a: &Histogram<K, S1, C>,
+    b: &Histogram<K, S2, C>,
+    cell: impl Fn(f64, f64) -> f64,
+) -> TestStatistic
+where
+    K: Hash + Eq,
+    S1: BuildHasher,
+    S2: BuildHasher,
+    C: Count,
+{
+    if a.is_empty() || b.is_empty() {
+        return TestStatistic {
+            statistic: 0.0,
+            degrees_of_freedom: 0,
+        };
+    }
+
+    let total_a = a.instances_f64();
+    let total_b = b.instances_f64();
+    let total = total_a + total_b;
+
+    let mut rows = 0;
+    let mut statistic = 0.0;
+    for (_key, cnt_a, cnt_b) in union_counts(a, b) {
+        rows += 1;
+        let (cnt_a, cnt_b) = (cnt_a.to_f64(), cnt_b.to_f64());
+        let row_total = cnt_a + cnt_b;
+        statistic += cell(cnt_a, row_total * total_a / total);
+        statistic += cell(cnt_b, row_total * total_b / total);
+    }
+
+    TestStatistic {
+        // Rounding errors could make the statistic slightly negative
+        statistic: statistic.max(0.0),
+        // (rows - 1) * (columns - 1) with two columns
+        degrees_of_freedom: rows - 1,
+    }
+}
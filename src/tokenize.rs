@@ -0,0 +1,106 @@
+//! Splitting text into the tokens that get counted.
+
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+use crate::{Count, Histogram};
+
+/// A way of splitting text into tokens, used by [`Histogram::count_text()`]
+///
+/// Implement this for custom tokenization rules, like splitting on punctuation or applying a
+/// subword vocabulary. Tokens borrow from the text, so tokenizing does not allocate.
+///
+/// # Example
+/// ```rust
+/// use histongram::{Histogram, Tokenizer};
+///
+/// /// Split on commas, dropping surrounding whitespace
+/// struct Commas;
+///
+/// impl Tokenizer for Commas {
+///     fn tokens<'a>(&self, text: &'a str) -> impl Iterator<Item = &'a str> {
+///         text.split(',').map(str::trim).filter(|token| !token.is_empty())
+///     }
+/// }
+///
+/// let mut hist = Histogram::<String>::new();
+/// hist.count_text("red, green,blue, red,", &Commas);
+/// assert_eq!(hist.count("red"), 2);
+/// assert_eq!(hist.num_instances(), 4);
+/// ```
+pub trait Tokenizer {
+    /// Split `text` into tokens
+    fn tokens<'a>(&self, text: &'a str) -> impl Iterator<Item = &'a str>;
+}
+
+/// Tokens separated by whitespace, like [`str::split_whitespace()`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Whitespace;
+
+impl Tokenizer for Whitespace {
+    fn tokens<'a>(&self, text: &'a str) -> impl Iterator<Item = &'a str> {
+        text.split_whitespace()
+    }
+}
+
+/// Words following the Unicode word boundary rules, see [`Histogram::count_unicode_words()`]
+#[cfg(feature = "unicode-segmentation")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode-segmentation")))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct UnicodeWords;
+
+#[cfg(feature = "unicode-segmentation")]
+impl Tokenizer for UnicodeWords {
+    fn tokens<'a>(&self, text: &'a str) -> impl Iterator<Item = &'a str> {
+        unicode_segmentation::UnicodeSegmentation::unicode_words(text)
+    }
+}
+
+/// Every match of the regular expression is a token
+///
+/// # Example
+/// ```rust
+/// use histongram::Histogram;
+/// use regex::Regex;
+///
+/// let hashtags = Regex::new(r"#\w+").unwrap();
+///
+/// let mut hist = Histogram::<String>::new();
+/// hist.count_text("#rust is fun, #rust #histogram", &hashtags);
+/// assert_eq!(hist.count("#rust"), 2);
+/// assert_eq!(hist.num_instances(), 3);
+/// ```
+#[cfg(feature = "regex")]
+#[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
+impl Tokenizer for regex::Regex {
+    fn tokens<'a>(&self, text: &'a str) -> impl Iterator<Item = &'a str> {
+        self.find_iter(text).map(|found| found.as_str())
+    }
+}
+
+impl<K, S, C> Histogram<K, S, C>
+where
+    K: Hash + Eq + Borrow<str> + for<'a> From<&'a str>,
+    S: BuildHasher,
+    C: Count,
+{
+    /// Count every token of `text` as split by `tokenizer`
+    ///
+    /// # Panics
+    /// If the count of a token overflows and the [`OverflowPolicy`](crate::OverflowPolicy) is
+    /// [`OverflowPolicy::Panic`](crate::OverflowPolicy::Panic).
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::{Histogram, Whitespace};
+    ///
+    /// let mut hist = Histogram::<String>::new();
+    /// hist.count_text("to be or not to be", &Whitespace);
+    /// assert_eq!(hist.count("be"), 2);
+    /// ```
+    pub fn count_text<T: Tokenizer>(&mut self, text: &str, tokenizer: &T) {
+        for token in tokenizer.tokens(text) {
+            self.add_ref(token);
+        }
+    }
+}
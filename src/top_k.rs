@@ -0,0 +1,372 @@
+//! Tracking the most common `key`s of a stream in bounded memory with the Space-Saving algorithm.
+
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+use hashbrown::{HashMap, HashTable};
+
+use crate::{Count, DefaultHashBuilder, Histogram};
+
+/// A Histogram that tracks at most `capacity` `key`s, keeping the most common ones
+///
+/// This implements the Space-Saving algorithm by Metwally, Agrawal and El Abbadi. As long as
+/// there is room every `key` is counted exactly. Once the capacity is reached, a new `key` replaces
+/// the tracked `key` with the smallest count and inherits that count as its possible error. So the
+/// count of a tracked `key` may be too high, but by at most its error. Every `key` that occurred
+/// more than `num_instances() / capacity` times is guaranteed to be tracked.
+///
+/// The tracked `key`s are kept in a min-heap by count, so adding takes `O(log capacity)` time,
+/// while looking up a `key` is as fast as for a [`Histogram`]. Counts saturate at the maximum of
+/// `C` instead of overflowing, as they are approximate anyway.
+///
+/// # Example
+/// ```rust
+/// use histongram::TopKHistogram;
+///
+/// let mut top = TopKHistogram::<String>::new(2);
+/// for status in ["200", "200", "404", "200", "500", "200"] {
+///     top.add_ref(status);
+/// }
+///
+/// assert_eq!(top.num_categories(), 2);
+/// assert_eq!(top.top_k(1), vec![(&"200".to_string(), 4)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TopKHistogram<K: Hash + Eq, S: BuildHasher = DefaultHashBuilder, C: Count = usize> {
+    /// Indices into `slots`, found by the hash of their `key`
+    table: HashTable<usize>,
+    /// The tracked `key`s, a slot is reused by the `key` replacing it
+    slots: Vec<Slot<K, C>>,
+    /// Indices into `slots`, forming a binary min-heap by count
+    heap: Vec<usize>,
+    hash_builder: S,
+    capacity: usize,
+    total: C,
+}
+
+/// A tracked `key` with its estimated count and how much it could be overestimated
+#[derive(Debug, Clone)]
+struct Slot<K, C> {
+    key: K,
+    hash: u64,
+    count: C,
+    error: C,
+    /// Position of this slot in the heap
+    heap_pos: usize,
+}
+
+impl<K: Hash + Eq, C: Count> TopKHistogram<K, DefaultHashBuilder, C> {
+    /// Create a new empty `TopKHistogram` tracking at most `capacity` `key`s
+    ///
+    /// # Panics
+    /// If `capacity` is `0`.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self::with_hasher(capacity, DefaultHashBuilder::default())
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher, C: Count> TopKHistogram<K, S, C> {
+    /// Create a new empty `TopKHistogram` tracking at most `capacity` `key`s using the given
+    /// `hash_builder`
+    ///
+    /// # Panics
+    /// If `capacity` is `0`.
+    #[must_use]
+    pub fn with_hasher(capacity: usize, hash_builder: S) -> Self {
+        assert!(
+            capacity > 0,
+            "a TopKHistogram has to track at least one key"
+        );
+
+        Self {
+            table: HashTable::new(),
+            slots: Vec::new(),
+            heap: Vec::new(),
+            hash_builder,
+            capacity,
+            total: C::ZERO,
+        }
+    }
+
+    /// Maximum number of tracked `key`s
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of currently tracked `key`s
+    #[must_use]
+    pub fn num_categories(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Total number of instances added so far, including those of `key`s no longer tracked
+    ///
+    /// In contrast to the counts of single `key`s this is exact.
+    #[must_use]
+    pub fn num_instances(&self) -> C {
+        self.total
+    }
+
+    /// Check if nothing was added so far
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Add a single occurrence of `val`, see [`Histogram::add_ref()`]
+    pub fn add_ref<'a, Q>(&mut self, val: &'a Q)
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q> + From<&'a Q>,
+    {
+        if let Some(slot) = self.find(val) {
+            self.total = self.total.saturating_add(C::ONE);
+            self.increase(slot, C::ONE);
+        } else {
+            self.add_owned_n(val.into(), C::ONE);
+        }
+    }
+
+    /// Add a single occurrence of `val`, see [`Histogram::add_owned()`]
+    pub fn add_owned(&mut self, val: K) {
+        self.add_owned_n(val, C::ONE);
+    }
+
+    /// Add `n` occurrences of `val`, see [`Histogram::add_owned_n()`]
+    pub fn add_owned_n(&mut self, val: K, n: C) {
+        if n.total_cmp(&C::ZERO).is_le() {
+            return;
+        }
+        self.total = self.total.saturating_add(n);
+
+        let hash = self.hash_builder.hash_one(&val);
+        let slots = &self.slots;
+        if let Some(&slot) = self.table.find(hash, |&slot| slots[slot].key == val) {
+            self.increase(slot, n);
+            return;
+        }
+
+        let slot = if self.slots.len() < self.capacity {
+            let slot = self.slots.len();
+            self.heap.push(slot);
+            self.slots.push(Slot {
+                key: val,
+                hash,
+                count: n,
+                error: C::ZERO,
+                heap_pos: slot,
+            });
+            self.sift_up(slot);
+            slot
+        } else {
+            // The root of the heap has the smallest count, so the new `key` takes over its slot
+            let slot = self.heap[0];
+            let evicted = &mut self.slots[slot];
+            if let Ok(entry) = self.table.find_entry(evicted.hash, |&other| other == slot) {
+                entry.remove();
+            }
+            evicted.key = val;
+            evicted.hash = hash;
+            evicted.error = evicted.count;
+            evicted.count = evicted.count.saturating_add(n);
+            self.sift_down(0);
+            slot
+        };
+        let slots = &self.slots;
+        self.table
+            .insert_unique(hash, slot, |&slot| slots[slot].hash);
+    }
+
+    /// Estimated count of `key`, which is `0` if it is not tracked
+    ///
+    /// The estimate is never too low for tracked `key`s, see [`TopKHistogram::count_bounds()`].
+    pub fn count<Q>(&self, key: &Q) -> C
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        self.find(key)
+            .map_or(C::ZERO, |slot| self.slots[slot].count)
+    }
+
+    /// Lower and upper bound of the true count of `key`, or `None` if it is not tracked
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::TopKHistogram;
+    ///
+    /// let mut top = TopKHistogram::<char>::new(1);
+    /// top.add_owned('a');
+    /// top.add_owned('b');
+    ///
+    /// // `b` replaced `a`, so one of its two counted instances might belong to `a`
+    /// assert_eq!(top.count_bounds(&'b'), Some((1, 2)));
+    /// assert_eq!(top.count_bounds(&'a'), None);
+    /// ```
+    pub fn count_bounds<Q>(&self, key: &Q) -> Option<(C, C)>
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        self.find(key).map(|slot| {
+            let tracked = &self.slots[slot];
+            (tracked.count.saturating_sub(tracked.error), tracked.count)
+        })
+    }
+
+    /// Iterate over all tracked `key`s and their estimated counts
+    ///
+    /// The order of keys is arbitrary.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, C)> {
+        self.slots
+            .iter()
+            .map(|tracked| (&tracked.key, tracked.count))
+    }
+
+    /// Get the `k` tracked `key`s with the highest estimated counts, sorted descending
+    ///
+    /// The order of `key`s with equal counts is arbitrary. See
+    /// [`TopKHistogram::guaranteed_top_k()`] for `key`s that are certainly among the most common.
+    #[must_use]
+    pub fn top_k(&self, k: usize) -> Vec<(&K, C)> {
+        let mut counts: Vec<_> = self.iter().collect();
+        // NOTE: unstable is okay here, as the map order is already arbitrary
+        counts.sort_unstable_by(|(_, a), (_, b)| b.total_cmp(a));
+        counts.truncate(k);
+        counts
+    }
+
+    /// Get those of the [`TopKHistogram::top_k()`] `key`s that are guaranteed to be among the `k`
+    /// most common `key`s of the whole stream
+    ///
+    /// A `key` is guaranteed if its lower bound is at least the estimated count of the `k + 1`th
+    /// `key`, as then no other tracked `key` can have occurred more often. Once the capacity is
+    /// reached, it also has to be at least the smallest tracked count, which bounds the count of
+    /// every `key` that is not tracked.
+    ///
+    /// # Example
+    /// ```rust
+    /// use histongram::TopKHistogram;
+    ///
+    /// let mut top = TopKHistogram::<char>::new(3);
+    /// top.extend_from_owned("aaaaabbbcd".chars());
+    ///
+    /// assert_eq!(top.guaranteed_top_k(1), vec![(&'a', 5)]);
+    /// ```
+    #[must_use]
+    pub fn guaranteed_top_k(&self, k: usize) -> Vec<(&K, C)> {
+        let mut tracked: Vec<_> = self.slots.iter().collect();
+        // NOTE: unstable is okay here, as the slot order is already arbitrary
+        tracked.sort_unstable_by(|a, b| b.count.total_cmp(&a.count));
+
+        let threshold = match tracked.get(k) {
+            Some(next) => next.count,
+            None if self.slots.len() >= self.capacity => {
+                tracked.last().map_or(C::ZERO, |min| min.count)
+            }
+            None => C::ZERO,
+        };
+        tracked
+            .into_iter()
+            .take(k)
+            .filter(|t| {
+                t.count
+                    .saturating_sub(t.error)
+                    .total_cmp(&threshold)
+                    .is_ge()
+            })
+            .map(|t| (&t.key, t.count))
+            .collect()
+    }
+
+    /// Add all owned `key`s of `iter`, see [`Histogram::extend_from_owned()`]
+    pub fn extend_from_owned<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for val in iter {
+            self.add_owned(val);
+        }
+    }
+
+    /// Convert into a regular [`Histogram`] of the tracked `key`s and their estimated counts
+    #[must_use]
+    pub fn into_histogram(self) -> Histogram<K, S, C> {
+        let mut map = HashMap::with_capacity_and_hasher(self.slots.len(), self.hash_builder);
+        map.extend(
+            self.slots
+                .into_iter()
+                .map(|tracked| (tracked.key, tracked.count)),
+        );
+        map.into()
+    }
+
+    /// Slot of the tracked `key`, if any
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        Q: ?Sized + Hash + Eq,
+        K: Borrow<Q>,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        self.table
+            .find(hash, |&slot| self.slots[slot].key.borrow() == key)
+            .copied()
+    }
+
+    /// Increase the count of the `key` in `slot` by `n`
+    fn increase(&mut self, slot: usize, n: C) {
+        let tracked = &mut self.slots[slot];
+        tracked.count = tracked.count.saturating_add(n);
+        let pos = tracked.heap_pos;
+        self.sift_down(pos);
+    }
+
+    /// Count of the slot at `pos` in the heap
+    fn heap_count(&self, pos: usize) -> C {
+        self.slots[self.heap[pos]].count
+    }
+
+    fn heap_swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.slots[self.heap[a]].heap_pos = a;
+        self.slots[self.heap[b]].heap_pos = b;
+    }
+
+    /// Move the slot at `pos` towards the root while it is smaller than its parent
+    fn sift_up(&mut self, mut pos: usize) {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if self
+                .heap_count(pos)
+                .total_cmp(&self.heap_count(parent))
+                .is_ge()
+            {
+                return;
+            }
+            self.heap_swap(pos, parent);
+            pos = parent;
+        }
+    }
+
+    /// Move the slot at `pos` towards the leaves while it is larger than one of its children
+    fn sift_down(&mut self, mut pos: usize) {
+        loop {
+            let mut smallest = pos;
+            for child in [2 * pos + 1, 2 * pos + 2] {
+                if child < self.heap.len()
+                    && self
+                        .heap_count(child)
+                        .total_cmp(&self.heap_count(smallest))
+                        .is_lt()
+                {
+                    smallest = child;
+                }
+            }
+            if smallest == pos {
+                return;
+            }
+            self.heap_swap(pos, smallest);
+            pos = smallest;
+        }
+    }
+}
@@ -1,12 +1,13 @@
 use std::collections::hash_map::RandomState;
 
-use histongram::{DefaultHashBuilder, Histogram};
+use histongram::{CountOverflow, DefaultHashBuilder, Histogram, OverflowPolicy, SortOrder};
 
 #[test]
 fn simple() {
     let mut h = Histogram::new();
     assert_eq!(h.num_categories(), 0);
     assert_eq!(h.num_instances(), 0);
+    assert!(h.is_empty());
 
     h.add_owned("a");
     assert_eq!(h.num_categories(), 1);
@@ -23,6 +24,10 @@ fn simple() {
     assert_eq!(h.count(&"a"), 2);
     assert_eq!(h.count(&"b"), 1);
     assert_eq!(h.count(&"c"), 0);
+
+    assert!(!h.is_empty());
+    assert!(h.contains_key(&"a"));
+    assert!(!h.contains_key(&"c"));
 }
 
 #[test]
@@ -115,3 +120,1787 @@ fn strong_hash() {
     h.add_owned("foo");
     assert_eq!(h.count(&"foo"), 1);
 }
+
+#[test]
+fn sorted_relative() {
+    let h = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aaabbc".chars());
+    assert_eq!(
+        h.sorted_occurrences_rel(),
+        vec![(&'a', 3.0 / 6.0), (&'b', 2.0 / 6.0), (&'c', 1.0 / 6.0)]
+    );
+
+    let empty = Histogram::<char>::new();
+    assert!(empty.sorted_occurrences_rel().is_empty());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn parallel_matches_serial() {
+    let text = include_str!("../LICENSE-APACHE");
+    let lines: Vec<&str> = text.lines().collect();
+
+    let serial: Histogram<&str> = text.split_whitespace().collect();
+    let parallel: Histogram<&str> =
+        histongram::parallel_count(lines, |line| line.split_whitespace().collect());
+
+    assert_eq!(serial.into_std_hash_map(), parallel.into_std_hash_map());
+}
+
+#[test]
+fn removing() {
+    let mut h = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aaabbc".chars());
+
+    assert_eq!(h.decrement(&'a'), 2);
+    assert_eq!(h.num_instances(), 5);
+    assert_eq!(h.num_categories(), 3);
+
+    assert_eq!(h.decrement(&'c'), 0);
+    assert_eq!(h.num_instances(), 4);
+    assert_eq!(h.num_categories(), 2);
+    assert!(!h.contains_key(&'c'));
+
+    assert_eq!(h.decrement(&'z'), 0);
+    assert_eq!(h.num_instances(), 4);
+
+    assert_eq!(h.remove(&'b'), 2);
+    assert_eq!(h.remove(&'b'), 0);
+    assert_eq!(h.num_instances(), 2);
+    assert_eq!(h.num_categories(), 1);
+    assert_eq!(h.count(&'a'), 2);
+}
+
+#[test]
+fn retaining() {
+    let mut h = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aaabbcdd".chars());
+
+    h.retain(|&key, _cnt| key != 'd');
+    assert_eq!(h.num_categories(), 3);
+    assert_eq!(h.num_instances(), 6);
+
+    h.prune_below(2);
+    assert_eq!(h.num_categories(), 2);
+    assert_eq!(h.num_instances(), 5);
+    assert_eq!(h.count(&'c'), 0);
+
+    h.prune_below(0);
+    assert_eq!(h.num_categories(), 2);
+}
+
+#[test]
+fn top_and_bottom() {
+    let h = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aaaabbbccd".chars());
+
+    assert_eq!(h.top_k(0), vec![]);
+    assert_eq!(h.top_k(1), vec![(&'a', 4)]);
+    assert_eq!(h.top_k(3), vec![(&'a', 4), (&'b', 3), (&'c', 2)]);
+    assert_eq!(h.top_k(10).len(), 4);
+
+    assert_eq!(h.bottom_k(0), vec![]);
+    assert_eq!(h.bottom_k(2), vec![(&'d', 1), (&'c', 2)]);
+    assert_eq!(h.bottom_k(10).len(), 4);
+
+    assert_eq!(h.top_k(usize::MAX).len(), 4);
+    assert_eq!(h.bottom_k(usize::MAX).len(), 4);
+
+    assert!(Histogram::<char>::new().top_k(5).is_empty());
+}
+
+#[test]
+fn entropy() {
+    let empty = Histogram::<char>::new();
+    assert_eq!(empty.entropy(), 0.0);
+    assert_eq!(empty.perplexity(), 1.0);
+
+    let single = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aaaa".chars());
+    assert_eq!(single.entropy(), 0.0);
+    assert_eq!(single.perplexity(), 1.0);
+
+    let h = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aabc".chars());
+    assert!((h.entropy() - 1.5).abs() < 1e-12);
+    assert!((h.entropy_nats() - 1.5 * std::f64::consts::LN_2).abs() < 1e-12);
+    assert!((h.perplexity() - 2.0f64.powf(1.5)).abs() < 1e-12);
+}
+
+#[test]
+fn kl_divergence() {
+    let p = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aabb".chars());
+    let q = Histogram::<_, RandomState>::from_owned_iter("abbb".chars());
+
+    assert_eq!(p.kl_divergence(&p), 0.0);
+    // 0.5 * log2(0.5 / 0.25) + 0.5 * log2(0.5 / 0.75)
+    let expected = 0.5 * 2.0f64.log2() + 0.5 * (2.0f64 / 3.0).log2();
+    assert!((p.kl_divergence(&q) - expected).abs() < 1e-12);
+
+    let only_b = Histogram::<_, DefaultHashBuilder>::from_owned_iter("bb".chars());
+    assert_eq!(p.kl_divergence(&only_b), f64::INFINITY);
+    assert!(p.kl_divergence_smoothed(&only_b, 1.0).is_finite());
+    assert!(only_b.kl_divergence(&p).is_finite());
+
+    let empty = Histogram::<char>::new();
+    assert_eq!(empty.kl_divergence(&p), 0.0);
+    assert_eq!(p.kl_divergence(&empty), f64::INFINITY);
+}
+
+#[test]
+fn jensen_shannon() {
+    let p = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aabb".chars());
+    let q = Histogram::<_, RandomState>::from_owned_iter("abbb".chars());
+    let disjoint = Histogram::<_, DefaultHashBuilder>::from_owned_iter("cd".chars());
+    let empty = Histogram::<char>::new();
+
+    assert_eq!(p.jensen_shannon(&p), 0.0);
+    assert_eq!(p.jensen_shannon(&disjoint), 1.0);
+    assert_eq!(empty.jensen_shannon(&empty), 0.0);
+    assert_eq!(empty.jensen_shannon(&p), 1.0);
+
+    let js = p.jensen_shannon(&q);
+    assert!(js > 0.0 && js < 1.0);
+    assert!((js - q.jensen_shannon(&p)).abs() < 1e-12);
+    assert!((p.jensen_shannon_distance(&q) - js.sqrt()).abs() < 1e-12);
+}
+
+#[test]
+fn cosine_similarity() {
+    let p = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aab".chars());
+    let q = Histogram::<_, RandomState>::from_owned_iter("abbc".chars());
+    let disjoint = Histogram::<_, DefaultHashBuilder>::from_owned_iter("xyz".chars());
+    let empty = Histogram::<char>::new();
+
+    assert!((p.cosine_similarity(&p) - 1.0).abs() < 1e-12);
+    assert_eq!(p.cosine_similarity(&disjoint), 0.0);
+    assert_eq!(p.cosine_similarity(&empty), 0.0);
+    assert_eq!(empty.cosine_similarity(&p), 0.0);
+
+    // (2 * 1 + 1 * 2) / (sqrt(5) * sqrt(6))
+    let expected = 4.0 / (5.0f64.sqrt() * 6.0f64.sqrt());
+    assert!((p.cosine_similarity(&q) - expected).abs() < 1e-12);
+    assert!((q.cosine_similarity(&p) - expected).abs() < 1e-12);
+}
+
+#[test]
+fn chi_squared_and_g_test() {
+    let a = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aaaaaaaabb".chars());
+    let b = Histogram::<_, RandomState>::from_owned_iter("aaaaabbbbbcc".chars());
+
+    // Hand computed for the table a: [8, 2, 0], b: [5, 5, 2]
+    let chi = a.chi_squared(&b);
+    assert_eq!(chi.degrees_of_freedom, 2);
+    assert!((chi.statistic - 3.827_838_828).abs() < 1e-6);
+    assert!((b.chi_squared(&a).statistic - chi.statistic).abs() < 1e-12);
+
+    let g = a.g_test(&b);
+    assert_eq!(g.degrees_of_freedom, 2);
+    assert!((g.statistic - 4.617_392_750).abs() < 1e-6);
+
+    assert_eq!(a.chi_squared(&a).statistic, 0.0);
+    assert_eq!(a.g_test(&a).statistic, 0.0);
+
+    let empty = Histogram::<char>::new();
+    assert_eq!(a.chi_squared(&empty).degrees_of_freedom, 0);
+    assert_eq!(empty.g_test(&a).statistic, 0.0);
+}
+
+#[test]
+fn adding() {
+    let a = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aab".chars());
+    let b = Histogram::<_, RandomState>::from_owned_iter("bc".chars());
+
+    let mut sum = a.clone() + &b;
+    assert_eq!(sum.count(&'a'), 2);
+    assert_eq!(sum.count(&'b'), 2);
+    assert_eq!(sum.count(&'c'), 1);
+
+    sum += &b;
+    assert_eq!(sum.num_instances(), 7);
+
+    sum += a.clone();
+    assert_eq!(sum.count(&'a'), 4);
+
+    let sum = sum + a;
+    assert_eq!(sum.count(&'a'), 6);
+    assert_eq!(sum.num_instances(), 13);
+    assert_eq!(b.num_instances(), 2);
+}
+
+#[test]
+fn subtracting() {
+    let a = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aaabbc".chars());
+    let b = Histogram::<_, RandomState>::from_owned_iter("abbbd".chars());
+
+    let mut diff = a.clone() - &b;
+    assert_eq!(diff.count(&'a'), 2);
+    assert_eq!(diff.count(&'c'), 1);
+    assert!(!diff.contains_key(&'b'));
+    assert!(!diff.contains_key(&'d'));
+    assert_eq!(diff.num_instances(), 3);
+
+    diff -= &a;
+    assert!(diff.is_empty());
+}
+
+#[test]
+fn set_operations() {
+    let a = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aaabbc".chars());
+    let b = Histogram::<_, RandomState>::from_owned_iter("abbbd".chars());
+
+    let union: Histogram<_> = a.union(&b);
+    let mut union = union.sorted_occurrences();
+    union.sort_unstable();
+    assert_eq!(union, vec![('a', 3), ('b', 3), ('c', 1), ('d', 1)]);
+
+    let intersection: Histogram<_> = a.intersection(&b);
+    let mut intersection = intersection.sorted_occurrences();
+    intersection.sort_unstable();
+    assert_eq!(intersection, vec![('a', 1), ('b', 2)]);
+
+    let difference: Histogram<_> = a.difference(&b);
+    assert_eq!(difference.sorted_occurrences(), vec![('c', 1)]);
+
+    let empty = Histogram::<char>::new();
+    assert!(a.intersection(&empty).is_empty());
+    assert_eq!(a.union(&empty).num_instances(), a.num_instances());
+    assert_eq!(a.difference(&empty).num_instances(), a.num_instances());
+}
+
+#[test]
+fn mapping_keys() {
+    let h = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aAbBBc".chars());
+
+    let lower: Histogram<char> = h.map_keys(|c| c.to_ascii_lowercase());
+    assert_eq!(lower.count(&'a'), 2);
+    assert_eq!(lower.count(&'b'), 3);
+    assert_eq!(lower.count(&'c'), 1);
+    assert_eq!(lower.num_instances(), 6);
+
+    let all_same: Histogram<()> = lower.map_keys(|_| ());
+    assert_eq!(all_same.count(&()), 6);
+    assert_eq!(all_same.num_categories(), 1);
+}
+
+#[test]
+fn other_count_types() {
+    let mut small = Histogram::<char, DefaultHashBuilder, u8>::default();
+    small.extend_from_owned("aaabbc".chars());
+    assert_eq!(small.count(&'a'), 3u8);
+    assert_eq!(small.num_instances(), 6u8);
+    assert_eq!(small.count_rel(&'a'), 0.5);
+    assert_eq!(small.top_k(1), vec![(&'a', 3u8)]);
+
+    let weighted: Histogram<&str, DefaultHashBuilder, f64> =
+        hashbrown::HashMap::from_iter([("a", 0.5), ("b", 1.5)]).into();
+    assert_eq!(weighted.num_instances(), 2.0);
+    assert_eq!(weighted.count_rel(&"b"), 0.75);
+    assert_eq!(
+        weighted.clone().sorted_occurrences(),
+        vec![("b", 1.5), ("a", 0.5)]
+    );
+
+    let mut weighted = weighted;
+    assert_eq!(weighted.decrement(&"a"), 0.0);
+    assert!(!weighted.contains_key(&"a"));
+    assert_eq!(weighted.decrement(&"b"), 0.5);
+}
+
+#[test]
+fn overflow_policies() {
+    let mut h = Histogram::<char, DefaultHashBuilder, u8>::default();
+    assert_eq!(h.overflow_policy(), OverflowPolicy::Panic);
+
+    h.extend_from_owned(std::iter::repeat_n('a', 255));
+    assert_eq!(h.try_add_owned('a'), Err(CountOverflow));
+    assert_eq!(h.count(&'a'), 255);
+    assert_eq!(h.try_add_owned('b'), Ok(()));
+
+    h.set_overflow_policy(OverflowPolicy::Saturate);
+    h.add_owned('a');
+    assert_eq!(h.count(&'a'), 255);
+
+    let other = h.clone();
+    h.append(other);
+    assert_eq!(h.count(&'a'), 255);
+    assert_eq!(h.count(&'b'), 2);
+}
+
+#[test]
+#[should_panic(expected = "count overflowed")]
+fn overflow_panics() {
+    let mut h = Histogram::<char, DefaultHashBuilder, u8>::default();
+    h.extend_from_owned(std::iter::repeat_n('a', 256));
+}
+
+#[test]
+fn totals_of_small_count_types() {
+    use histongram::{OrderedHistogram, SmallHistogram};
+
+    let mut hist = Histogram::<u32, DefaultHashBuilder, u8>::default();
+    hist.add_owned_n(0, 200);
+    hist.add_owned_n(1, 200);
+
+    // Relative counts do not depend on the total fitting into `u8`
+    assert_eq!(hist.count_rel(&0), 0.5);
+    assert_eq!(hist.iter_rel().map(|(_, rel)| rel).sum::<f64>(), 1.0);
+    assert_eq!(hist.entropy(), 1.0);
+
+    // The total itself follows the overflow policy
+    let panicked = std::panic::catch_unwind(|| hist.num_instances());
+    assert!(panicked.is_err());
+    hist.set_overflow_policy(OverflowPolicy::Saturate);
+    assert_eq!(hist.num_instances(), u8::MAX);
+    assert_eq!(hist.iter_cumulative().last().unwrap().1, u8::MAX);
+
+    let mut small = SmallHistogram::<u32, u8>::default();
+    small.set_overflow_policy(OverflowPolicy::Saturate);
+    small.add_owned_n(0, 200);
+    small.add_owned_n(1, 200);
+    assert_eq!(small.num_instances(), u8::MAX);
+    assert_eq!(small.count_rel(&1), 0.5);
+
+    let mut ordered = OrderedHistogram::<u32, u8>::default();
+    ordered.set_overflow_policy(OverflowPolicy::Saturate);
+    ordered.add_owned_n(0, 200);
+    ordered.add_owned_n(1, 200);
+    assert_eq!(ordered.num_instances(), u8::MAX);
+    assert_eq!(ordered.range_count(0..2), u8::MAX);
+}
+
+#[test]
+fn weighted_adding() {
+    let mut h = Histogram::<String>::new();
+    h.add_ref_n("a", 3);
+    h.add_owned_n("b".to_string(), 2);
+    h.add_owned_n("c".to_string(), 0);
+    assert_eq!(h.count("a"), 3);
+    assert_eq!(h.count("b"), 2);
+    assert!(!h.contains_key("c"));
+
+    h.extend([("a".to_string(), 2), ("d".to_string(), 1)]);
+    assert_eq!(h.count("a"), 5);
+    assert_eq!(h.num_instances(), 8);
+
+    let collected: Histogram<char> = [('x', 2), ('y', 1), ('x', 3)].into_iter().collect();
+    assert_eq!(collected.count(&'x'), 5);
+    assert_eq!(collected.num_categories(), 2);
+
+    let mut weighted = Histogram::<&str, DefaultHashBuilder, f64>::default();
+    weighted.add_owned_n("a", 0.25);
+    weighted.add_owned_n("a", 0.5);
+    assert_eq!(weighted.count(&"a"), 0.75);
+
+    weighted.add_owned_n("nan", f64::NAN);
+    weighted.add_ref_n("negative", -1.0);
+    assert_eq!(weighted.num_categories(), 1);
+    assert_eq!(weighted.num_instances(), 0.75);
+}
+
+#[test]
+fn draining() {
+    let mut h = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aaabbc".chars());
+
+    let mut drained: Vec<_> = h.drain().collect();
+    drained.sort_unstable();
+    assert_eq!(drained, vec![('a', 3), ('b', 2), ('c', 1)]);
+    assert!(h.is_empty());
+    assert_eq!(h.num_instances(), 0);
+
+    h.extend_from_owned("xy".chars());
+    assert_eq!(h.num_instances(), 2);
+}
+
+#[test]
+fn sort_orders() {
+    let h = Histogram::<_, DefaultHashBuilder>::from_owned_iter("ddccbbbaaaae".chars());
+
+    assert_eq!(
+        h.clone().sorted_occurrences_in(SortOrder::CountDescending),
+        vec![('a', 4), ('b', 3), ('c', 2), ('d', 2), ('e', 1)]
+    );
+    assert_eq!(
+        h.clone().sorted_occurrences_in(SortOrder::CountAscending),
+        vec![('e', 1), ('c', 2), ('d', 2), ('b', 3), ('a', 4)]
+    );
+    assert_eq!(
+        h.clone().sorted_occurrences_in(SortOrder::KeyAscending),
+        vec![('a', 4), ('b', 3), ('c', 2), ('d', 2), ('e', 1)]
+    );
+    assert_eq!(
+        h.clone().sorted_occurrences_in(SortOrder::KeyDescending),
+        vec![('e', 1), ('d', 2), ('c', 2), ('b', 3), ('a', 4)]
+    );
+    assert_eq!(
+        h.sorted_occurrences_by(|(_, a), (_, b)| a.cmp(b)).first(),
+        Some(&('e', 1))
+    );
+}
+
+#[test]
+fn capacity_management() {
+    let mut h = Histogram::<char>::with_capacity(64);
+    assert!(h.capacity() >= 64);
+    assert!(h.is_empty());
+
+    h.extend_from_owned("abc".chars());
+    h.clear();
+    assert!(h.is_empty());
+    assert!(h.capacity() >= 64);
+
+    h.add_owned('a');
+    h.shrink_to_fit();
+    assert!(h.capacity() < 64);
+    h.reserve(32);
+    assert!(h.capacity() >= 33);
+    assert_eq!(h.count(&'a'), 1);
+
+    let h = Histogram::<char, _>::with_capacity_and_hasher(10, RandomState::new());
+    assert!(h.capacity() >= 10);
+}
+
+#[test]
+fn most_and_least_common() {
+    let h = Histogram::<_, DefaultHashBuilder>::from_owned_iter("ddccbbbaaaae".chars());
+    assert_eq!(h.most_common(), Some((&'a', 4)));
+    assert_eq!(h.least_common(), Some((&'e', 1)));
+    assert_eq!(h.max_count(), Some(4));
+    assert_eq!(h.min_count(), Some(1));
+
+    let ties = Histogram::<_, DefaultHashBuilder>::from_owned_iter("zzyyxxcba".chars());
+    assert_eq!(ties.most_common_ordered(), Some((&'x', 2)));
+    assert_eq!(ties.least_common_ordered(), Some((&'a', 1)));
+
+    let empty = Histogram::<char>::new();
+    assert_eq!(empty.most_common(), None);
+    assert_eq!(empty.least_common_ordered(), None);
+    assert_eq!(empty.max_count(), None);
+    assert_eq!(empty.min_count(), None);
+}
+
+#[test]
+fn hapax_and_type_token_ratio() {
+    let h = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aaabcd".chars());
+    assert_eq!(h.hapax_count(), 3);
+    let mut hapaxes: Vec<_> = h.hapax_iter().copied().collect();
+    hapaxes.sort_unstable();
+    assert_eq!(hapaxes, vec!['b', 'c', 'd']);
+    assert_eq!(h.type_token_ratio(), 4.0 / 6.0);
+
+    let empty = Histogram::<char>::new();
+    assert_eq!(empty.hapax_count(), 0);
+    assert_eq!(empty.type_token_ratio(), 0.0);
+}
+
+#[test]
+fn zipf_fit() {
+    let exact = Histogram::from_counts((1..=50).map(|rank| (rank, 1000 / rank)));
+    let fit = exact.zipf_fit().unwrap();
+    assert!((fit.exponent - 1.0).abs() < 0.01);
+    assert!(fit.r_squared > 0.99);
+
+    let flat = Histogram::from_counts([('a', 5), ('b', 5), ('c', 5)]);
+    let fit = flat.zipf_fit().unwrap();
+    assert_eq!(fit.exponent, 0.0);
+    assert_eq!(fit.r_squared, 1.0);
+
+    assert_eq!(Histogram::from_counts([('a', 5)]).zipf_fit(), None);
+}
+
+#[test]
+fn diversity_indices() {
+    let h = Histogram::<_, DefaultHashBuilder>::from_owned_iter("aaabbc".chars());
+    let (a, b, c) = (3.0 / 6.0, 2.0 / 6.0, 1.0 / 6.0);
+
+    let simpson: f64 = a * a + b * b + c * c;
+    assert!((h.simpson_index() - simpson).abs() < 1e-12);
+    assert!((h.gini_simpson_index() - (1.0 - simpson)).abs() < 1e-12);
+
+    // Mean absolute difference of [1, 2, 3] is 8 / 9, divided by twice the mean of 2
+    assert!((h.gini_coefficient() - 2.0 / 9.0).abs() < 1e-12);
+
+    let entropy = -(a * f64::log2(a) + b * f64::log2(b) + c * f64::log2(c));
+    assert!((h.shannon_evenness() - entropy / 3.0f64.log2()).abs() < 1e-12);
+
+    let empty = Histogram::<char>::new();
+    assert_eq!(empty.simpson_index(), 0.0);
+    assert_eq!(empty.gini_simpson_index(), 0.0);
+    assert_eq!(empty.gini_coefficient(), 0.0);
+    assert_eq!(empty.shannon_evenness(), 1.0);
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn sampling() {
+    use histongram::SampledDistribution;
+    use rand::distributions::Distribution;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let mut rng = StdRng::seed_from_u64(7);
+    let hist = Histogram::<_>::from_owned_iter("aaaaaaab".chars());
+
+    let drawn = Histogram::<_>::from_owned_iter(hist.sample_iter(&mut rng).take(8000).copied());
+    assert_eq!(drawn.num_instances(), 8000);
+    assert!((drawn.count_rel(&'a') - 0.875).abs() < 0.02);
+
+    let dist = SampledDistribution::new(&hist).unwrap();
+    assert_eq!(dist.len(), 2);
+    let drawn = Histogram::<_>::from_owned_iter(dist.sample_iter(&mut rng).take(8000).copied());
+    assert!((drawn.count_rel(&'b') - 0.125).abs() < 0.02);
+
+    let single =
+        Histogram::<_>::from_owned_iter((0..8000).filter_map(|_| hist.sample(&mut rng).copied()));
+    assert!((single.count_rel(&'b') - 0.125).abs() < 0.02);
+
+    let empty = Histogram::<char>::new();
+    assert!(SampledDistribution::new(&empty).is_none());
+    assert_eq!(empty.sample_iter(&mut rng).next(), None);
+}
+
+#[test]
+fn cumulative_coverage() {
+    let hist = Histogram::<_>::from_owned_iter("aaaaaabbbc".chars());
+
+    let cumulative: Vec<_> = hist.iter_cumulative().collect();
+    assert_eq!(
+        cumulative,
+        vec![(&'a', 6, 0.6), (&'b', 9, 0.9), (&'c', 10, 1.0)]
+    );
+
+    assert_eq!(hist.coverage(0.0), 0);
+    assert_eq!(hist.coverage(0.6), 1);
+    assert_eq!(hist.coverage(0.61), 2);
+    assert_eq!(hist.coverage(1.0), 3);
+    assert_eq!(hist.coverage(2.0), 3);
+
+    let empty = Histogram::<char>::new();
+    assert_eq!(empty.iter_cumulative().next(), None);
+    assert_eq!(empty.coverage(0.5), 0);
+}
+
+#[test]
+fn add_k_smoothing() {
+    let hist = Histogram::<_>::from_owned_iter("aaab".chars());
+
+    let laplace = hist.smoothed(1.0, 4);
+    assert_eq!(laplace.prob(&'a'), 0.5);
+    assert_eq!(laplace.prob(&'b'), 0.25);
+    assert_eq!(laplace.prob(&'c'), 0.125);
+    assert_eq!(laplace.unseen_prob(), 0.125);
+    assert_eq!(laplace.log_prob(&'a'), 0.5f64.ln());
+
+    // Too small vocabularies are widened to the observed categories
+    let unsmoothed = hist.smoothed(0.0, 0);
+    assert_eq!(unsmoothed.prob(&'a'), 0.75);
+    assert_eq!(unsmoothed.unseen_prob(), 0.0);
+
+    let empty = Histogram::<char>::new();
+    assert_eq!(empty.smoothed(1.0, 4).prob(&'a'), 0.25);
+    assert_eq!(empty.smoothed(0.0, 4).prob(&'a'), 0.0);
+}
+
+#[test]
+fn good_turing() {
+    let hist = Histogram::<_>::from_counts([
+        ("a", 1),
+        ("b", 1),
+        ("c", 1),
+        ("d", 1),
+        ("e", 2),
+        ("f", 2),
+        ("g", 3),
+        ("h", 5),
+    ]);
+    assert_eq!(
+        hist.count_spectrum().into_iter().collect::<Vec<_>>(),
+        vec![(1, 4), (2, 2), (3, 1), (5, 1)]
+    );
+
+    let gt = hist.good_turing().unwrap();
+    assert_eq!(gt.unseen_mass(), 4.0 / 16.0);
+    assert_eq!(gt.unseen_prob(4), 1.0 / 16.0);
+    assert_eq!(gt.unseen_prob(0), 0.0);
+
+    let seen: f64 = hist.iter().map(|(key, _cnt)| gt.prob(key)).sum();
+    assert!((seen + gt.unseen_mass() - 1.0).abs() < 1e-9);
+
+    // Singletons are discounted, and keys with equal counts get equal estimates
+    assert!(gt.adjusted_count("a") < 1.0);
+    assert_eq!(gt.prob("a"), gt.prob("d"));
+    assert!(gt.prob("a") < gt.prob("e"));
+    assert!(gt.prob("g") < gt.prob("h"));
+    assert_eq!(gt.prob("z"), 0.0);
+    assert_eq!(gt.adjusted_count("z"), 0.0);
+
+    assert!(Histogram::<&str>::new().good_turing().is_none());
+
+    // Without singletons nothing is reserved for unseen keys
+    let hist = Histogram::<_>::from_owned_iter("aabb".chars());
+    let gt = hist.good_turing().unwrap();
+    assert_eq!(gt.unseen_mass(), 0.0);
+    assert_eq!(gt.prob(&'a'), 0.5);
+}
+
+#[test]
+fn total_variation_and_hellinger() {
+    let p = Histogram::<_>::from_owned_iter("aaab".chars());
+    let q = Histogram::<_>::from_owned_iter("abbb".chars());
+    let r = Histogram::<_>::from_owned_iter("cd".chars());
+    let empty = Histogram::<char>::new();
+
+    assert_eq!(p.total_variation(&p), 0.0);
+    assert_eq!(p.total_variation(&q), 0.5);
+    assert_eq!(q.total_variation(&p), 0.5);
+    assert_eq!(p.total_variation(&r), 1.0);
+    assert_eq!(p.total_variation(&empty), 1.0);
+    assert_eq!(empty.total_variation(&empty), 0.0);
+
+    assert_eq!(p.hellinger(&p), 0.0);
+    let expected = (1.0 - 2.0 * (0.75f64 * 0.25).sqrt()).sqrt();
+    assert!((p.hellinger(&q) - expected).abs() < 1e-12);
+    assert_eq!(p.hellinger(&r), 1.0);
+    assert_eq!(empty.hellinger(&p), 1.0);
+    assert_eq!(empty.hellinger(&empty), 0.0);
+}
+
+#[test]
+fn keys_and_counts() {
+    let hist = Histogram::<String>::from_iter(["x", "y", "x", "z", "x"]);
+
+    let mut keys: Vec<_> = hist.keys().cloned().collect();
+    keys.sort_unstable();
+    assert_eq!(keys, vec!["x", "y", "z"]);
+
+    let mut counts: Vec<_> = hist.counts().collect();
+    counts.sort_unstable();
+    assert_eq!(counts, vec![1, 1, 3]);
+
+    let paired: Vec<_> = hist.keys().zip(hist.counts()).collect();
+    assert_eq!(paired, hist.iter().collect::<Vec<_>>());
+
+    assert!(hist.contains("x"));
+    assert!(!hist.contains("w"));
+
+    let empty = Histogram::<String>::new();
+    assert_eq!(empty.keys().next(), None);
+    assert_eq!(empty.counts().next(), None);
+}
+
+#[test]
+fn indexing() {
+    let hist = Histogram::<_>::from_owned_iter("aab".chars());
+    assert_eq!(hist[&'a'], 2);
+    assert_eq!(hist[&'b'], 1);
+    assert_eq!(hist[&'z'], 0);
+
+    let mut weighted = Histogram::<char, DefaultHashBuilder, f64>::default();
+    weighted.add_owned_n('a', 0.5);
+    assert_eq!(weighted[&'a'], 0.5);
+    assert_eq!(weighted[&'z'], 0.0);
+}
+
+#[test]
+fn borrowed_lookup() {
+    let hist = Histogram::<String>::from_iter(["word", "word", "other"]);
+    let word: &str = "word";
+
+    assert_eq!(hist.count(word), 2);
+    assert_eq!(hist["word"], 2);
+    assert_eq!(hist[&"word".to_string()], 2);
+    assert!(hist.contains(word));
+    assert_eq!(hist.count_rel(word), 2.0 / 3.0);
+
+    let bytes = Histogram::<_>::from_owned_iter([b"ab".to_vec(), b"cd".to_vec()]);
+    assert_eq!(bytes.count(&b"ab"[..]), 1);
+    assert_eq!(bytes[&b"cd"[..]], 1);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn parallel_collect_and_extend() {
+    use rayon::iter::{IntoParallelIterator, ParallelExtend, ParallelIterator};
+
+    let text = include_str!("../LICENSE-APACHE");
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let serial: Histogram<String> = words.iter().copied().collect();
+
+    let parallel: Histogram<String> = words.clone().into_par_iter().collect();
+    assert_eq!(
+        serial.clone().into_std_hash_map(),
+        parallel.into_std_hash_map()
+    );
+
+    let mut extended: Histogram<String> = words.iter().copied().collect();
+    extended.par_extend(words.clone().into_par_iter());
+    assert_eq!(extended.num_instances(), 2 * words.len());
+    assert_eq!(extended.count("License"), 2 * serial.count("License"));
+
+    let pairs: Histogram<String> = serial
+        .iter()
+        .map(|(key, cnt)| (key.clone(), cnt))
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .collect();
+    assert_eq!(pairs.into_std_hash_map(), serial.into_std_hash_map());
+}
+
+#[test]
+fn merging_many() {
+    let text = include_str!("../LICENSE-APACHE");
+    let serial: Histogram<&str> = text.split_whitespace().collect();
+    let shards: Vec<Histogram<&str>> = text
+        .lines()
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+
+    let merged = Histogram::merge_all(shards.clone());
+    assert_eq!(
+        merged.into_std_hash_map(),
+        serial.clone().into_std_hash_map()
+    );
+
+    #[cfg(feature = "rayon")]
+    {
+        let merged = Histogram::par_merge_all(shards);
+        assert_eq!(merged.into_std_hash_map(), serial.into_std_hash_map());
+    }
+
+    let empty = Histogram::<&str>::merge_all([]);
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn concurrent_counting() {
+    use histongram::ConcurrentHistogram;
+
+    let text = include_str!("../LICENSE-APACHE");
+    let serial: Histogram<&str> = text.split_whitespace().collect();
+
+    let concurrent = ConcurrentHistogram::<&str>::with_shards(8);
+    assert_eq!(concurrent.num_shards(), 8);
+    std::thread::scope(|s| {
+        for line in text.lines() {
+            let concurrent = &concurrent;
+            s.spawn(move || {
+                for word in line.split_whitespace() {
+                    concurrent.add_owned(word);
+                }
+            });
+        }
+    });
+
+    assert_eq!(concurrent.num_instances(), serial.num_instances());
+    assert_eq!(concurrent.num_categories(), serial.num_categories());
+    assert_eq!(concurrent.count("License"), serial.count("License"));
+    assert_eq!(
+        concurrent.snapshot().into_std_hash_map(),
+        serial.clone().into_std_hash_map()
+    );
+    assert_eq!(
+        concurrent.into_histogram().into_std_hash_map(),
+        serial.into_std_hash_map()
+    );
+
+    let strings = ConcurrentHistogram::<String>::new();
+    strings.add_ref("a");
+    strings.add_owned_n("a".to_string(), 2);
+    assert_eq!(strings.count("a"), 3);
+    assert_eq!(strings.count("b"), 0);
+}
+
+#[test]
+fn count_min_sketch() {
+    use histongram::CountMinSketch;
+
+    let text = include_str!("../LICENSE-APACHE");
+    let exact: Histogram<&str> = text.split_whitespace().collect();
+
+    let mut sketch = CountMinSketch::<str>::new(0.01, 0.01);
+    assert_eq!(sketch.width(), 272);
+    assert_eq!(sketch.depth(), 5);
+    assert!(sketch.is_empty());
+    for word in text.split_whitespace() {
+        sketch.add_ref(word);
+    }
+
+    assert_eq!(sketch.num_instances(), exact.num_instances());
+    // The bound only holds with high probability, so allow a few outliers
+    let bound = sketch.error_bound();
+    let mut outliers = 0;
+    for (word, cnt) in &exact {
+        let estimate = sketch.count(*word);
+        assert!(estimate >= cnt);
+        if (estimate - cnt) as f64 > bound {
+            outliers += 1;
+        }
+    }
+    assert!(outliers * 20 < exact.num_categories());
+
+    let mut owned = CountMinSketch::<String, _, u32>::with_dimensions(64, 4);
+    owned.add_owned("a".to_string());
+    owned.add_ref_n("a", 2);
+    assert_eq!(owned.count("a"), 3);
+    let heavy = owned.heavy_hitters(["a".to_string(), "b".to_string()], 1);
+    assert_eq!(
+        heavy.into_std_hash_map(),
+        std::collections::HashMap::from([("a".to_string(), 3)])
+    );
+
+    owned.clear();
+    assert!(owned.is_empty());
+    assert_eq!(owned.count("a"), 0);
+}
+
+#[test]
+#[should_panic(expected = "sketch dimensions overflow usize")]
+fn count_min_sketch_dimensions_overflow() {
+    use histongram::CountMinSketch;
+
+    let _ = CountMinSketch::<u32>::with_dimensions(usize::MAX, 2);
+}
+
+#[test]
+fn space_saving_top_k() {
+    use histongram::TopKHistogram;
+
+    // Zipf-like stream, where `a` is much more common than everything else
+    let mut stream = String::new();
+    for (i, c) in "abcdefghijklmnopqrstuvwxyz".chars().enumerate() {
+        stream.extend(std::iter::repeat_n(c, 100 / (i + 1)));
+    }
+    let exact = Histogram::<_>::from_owned_iter(stream.chars());
+
+    let mut top = TopKHistogram::<char>::new(8);
+    top.extend_from_owned(stream.chars());
+    assert_eq!(top.capacity(), 8);
+    assert_eq!(top.num_categories(), 8);
+    assert_eq!(top.num_instances(), exact.num_instances());
+
+    for (key, cnt) in top.iter() {
+        let (lower, upper) = top.count_bounds(key).unwrap();
+        assert_eq!(upper, cnt);
+        assert!(lower <= exact.count(key) && exact.count(key) <= upper);
+    }
+    // Everything more common than `num_instances / capacity` is tracked
+    let threshold = top.num_instances() / top.capacity();
+    for (key, cnt) in &exact {
+        if cnt > threshold {
+            assert!(top.count_bounds(key).is_some());
+        }
+    }
+
+    assert_eq!(top.top_k(1), vec![(&'a', 100)]);
+    assert_eq!(top.guaranteed_top_k(2), vec![(&'a', 100), (&'b', 50)]);
+    assert_eq!(top.count(&'?'), 0);
+    assert_eq!(top.count_bounds(&'?'), None);
+
+    let hist = top.into_histogram();
+    assert_eq!(hist.num_categories(), 8);
+    assert_eq!(hist.count(&'a'), 100);
+
+    // `b` might only have occurred once, so it is not guaranteed to be more common than `a`
+    let mut full = TopKHistogram::<char>::new(1);
+    full.extend_from_owned("aab".chars());
+    assert_eq!(full.top_k(1), vec![(&'b', 3)]);
+    assert_eq!(full.guaranteed_top_k(1), vec![]);
+}
+
+#[test]
+fn cardinality_estimation() {
+    use histongram::CardinalityEstimator;
+
+    // Fixed hasher, as a collision of the randomly seeded default one would be off by one
+    let hasher =
+        std::hash::BuildHasherDefault::<std::collections::hash_map::DefaultHasher>::default();
+    let mut small = CardinalityEstimator::<u32, _>::with_precision_and_hasher(12, hasher);
+    assert!(small.is_empty());
+    assert_eq!(small.estimate(), 0.0);
+    for i in 0..100 {
+        small.add_owned(i % 10);
+    }
+    assert_eq!(small.estimate().round(), 10.0);
+
+    let mut a = CardinalityEstimator::<u32>::with_precision(10);
+    // Clone while empty, so both use the same hasher
+    let mut b = a.clone();
+    for i in 0..50_000 {
+        a.add_ref(&i);
+        b.add_ref(&(i + 25_000));
+    }
+    let tolerance = 4.0 * a.standard_error();
+    assert!((a.estimate() / 50_000.0 - 1.0).abs() < tolerance);
+
+    a.merge(&b);
+    assert!((a.estimate() / 75_000.0 - 1.0).abs() < tolerance);
+
+    a.clear();
+    assert!(a.is_empty());
+}
+
+#[test]
+fn decaying_counts() {
+    use histongram::DecayingHistogram;
+
+    let mut recent = DecayingHistogram::<String>::new(10.0);
+    assert_eq!(recent.half_life(), 10.0);
+    recent.add_ref("a");
+    recent.add_owned_n("b".to_string(), 4.0);
+    recent.advance(10.0);
+    recent.add_owned("a".to_string());
+
+    assert_eq!(recent.count("a"), 1.5);
+    assert_eq!(recent.count("b"), 2.0);
+    assert_eq!(recent.count("c"), 0.0);
+    assert_eq!(recent.num_instances(), 3.5);
+    assert_eq!(recent.num_categories(), 2);
+    assert_eq!(
+        recent.top_k(2),
+        vec![(&"b".to_string(), 2.0), (&"a".to_string(), 1.5)]
+    );
+
+    let mut counts: Vec<_> = recent.iter().map(|(k, c)| (k.clone(), c)).collect();
+    counts.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(counts, vec![("a".to_string(), 1.5), ("b".to_string(), 2.0)]);
+    assert_eq!(recent.snapshot().count("b"), 2.0);
+
+    // Long runs of time are rescaled internally without losing precision
+    for _ in 0..1000 {
+        recent.advance(10.0);
+    }
+    recent.add_ref("c");
+    assert_eq!(recent.count("c"), 1.0);
+    assert!(recent.count("b") < 1e-100);
+
+    recent.prune_below(0.5);
+    assert_eq!(recent.num_categories(), 1);
+    let hist = recent.into_histogram();
+    assert_eq!(hist.count("c"), 1.0);
+
+    // After a very long time nothing is left, instead of categories with a count of zero
+    let mut ancient = DecayingHistogram::<String>::new(1.0);
+    ancient.add_ref("a");
+    ancient.advance(1e6);
+    assert!(ancient.is_empty());
+    assert_eq!(ancient.num_categories(), 0);
+    ancient.add_ref("b");
+    assert_eq!(ancient.count("b"), 1.0);
+}
+
+#[test]
+fn binary_roundtrip() {
+    use std::io;
+
+    let text = include_str!("../LICENSE-APACHE");
+    let hist: Histogram<String> = text.split_whitespace().collect();
+
+    let mut buf = Vec::new();
+    hist.write_to(&mut buf).unwrap();
+    assert_eq!(&buf[..5], b"HGRM\x01");
+    let loaded = Histogram::<String>::read_from(&buf[..]).unwrap();
+    assert_eq!(loaded.into_std_hash_map(), hist.clone().into_std_hash_map());
+
+    let bigrams = Histogram::<(char, char), DefaultHashBuilder, f64>::from_iter([
+        (('a', 'b'), 0.5),
+        (('b', 'c'), 2.0),
+    ]);
+    let mut buf = Vec::new();
+    bigrams.write_to(&mut buf).unwrap();
+    let loaded = Histogram::<(char, char), DefaultHashBuilder, f64>::read_from(&buf[..]).unwrap();
+    assert_eq!(loaded.count(&('b', 'c')), 2.0);
+    assert_eq!(loaded.num_categories(), 2);
+
+    let signed = Histogram::<_>::from_owned_iter([-1_i64, 0, i64::MIN, i64::MAX, -1]);
+    let mut buf = Vec::new();
+    signed.write_to(&mut buf).unwrap();
+    let loaded = Histogram::<i64>::read_from(&buf[..]).unwrap();
+    assert_eq!(loaded.into_std_hash_map(), signed.into_std_hash_map());
+
+    let err = Histogram::<String>::read_from(&b"JSON{}"[..]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    let mut truncated = Vec::new();
+    hist.write_to(&mut truncated).unwrap();
+    truncated.truncate(truncated.len() / 2);
+    let err = Histogram::<String>::read_from(&truncated[..]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+    // Two categories, both with key `7`
+    let duplicate = b"HGRM\x01\x02\x07\x01\x07\x01";
+    let err = Histogram::<u8>::read_from(&duplicate[..]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    // One category, key `7` with a count of zero
+    let zero = b"HGRM\x01\x01\x07\x00";
+    let err = Histogram::<u8>::read_from(&zero[..]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    let max = Histogram::<_>::from_owned_iter([u128::MAX]);
+    let mut buf = Vec::new();
+    max.write_to(&mut buf).unwrap();
+    assert_eq!(Histogram::<u128>::read_from(&buf[..]).unwrap(), max);
+    // Set a bit above the top bit of a `u128` in the last byte of the key
+    buf[6 + 18] = 0x04;
+    let err = Histogram::<u128>::read_from(&buf[..]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn csv_roundtrip() {
+    use histongram::{CsvError, CsvOptions, SortOrder};
+
+    let hist: Histogram<String> = ["b", "a, quoted", "b", "c"].into_iter().collect();
+
+    let options = CsvOptions { header: false };
+    let mut buf = Vec::new();
+    hist.to_sorted_csv_writer(&mut buf, &options, SortOrder::KeyAscending)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(buf.clone()).unwrap(),
+        "\"a, quoted\",1\nb,2\nc,1\n"
+    );
+
+    let loaded = Histogram::<String>::from_csv_reader(&buf[..], &options).unwrap();
+    assert_eq!(loaded.into_std_hash_map(), hist.into_std_hash_map());
+
+    let err = Histogram::<String>::from_csv_reader(
+        "key,count\na,1\nb,many\n".as_bytes(),
+        &CsvOptions::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, CsvError::InvalidCount { line: 3, .. }));
+
+    let err = Histogram::<String>::from_csv_reader("a,1,2\n".as_bytes(), &options).unwrap_err();
+    assert!(matches!(err, CsvError::InvalidRow { line: 1 }));
+
+    // Keys without `Ord` can be written unsorted
+    #[derive(Hash, PartialEq, Eq)]
+    struct Unordered(&'static str);
+    impl std::fmt::Display for Unordered {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+    let unordered = Histogram::<_>::from_owned_iter([Unordered("x"), Unordered("x")]);
+    let mut buf = Vec::new();
+    unordered.to_csv_writer(&mut buf, &options).unwrap();
+    assert_eq!(buf, b"x,2\n");
+
+    let err = Histogram::<String, DefaultHashBuilder, u8>::from_csv_reader(
+        "a,200\na,100\n".as_bytes(),
+        &options,
+    )
+    .unwrap_err();
+    assert!(matches!(err, CsvError::CountOverflow { line: 2 }));
+    for count in ["-1", "NaN"] {
+        let csv = format!("a,{count}\n");
+        let err =
+            Histogram::<String, DefaultHashBuilder, f64>::from_csv_reader(csv.as_bytes(), &options)
+                .unwrap_err();
+        assert!(matches!(err, CsvError::InvalidCount { line: 1, .. }));
+    }
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn arrow_export() {
+    use arrow_array::{Array, StringArray, UInt64Array};
+
+    let hist: Histogram<String> = ["a", "b", "a", "c", "a"].into_iter().collect();
+    let batch = hist.to_arrow();
+    assert_eq!(batch.num_rows(), 3);
+
+    let keys = batch
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    let counts = batch
+        .column(1)
+        .as_any()
+        .downcast_ref::<UInt64Array>()
+        .unwrap();
+    let mut rows: Vec<_> = keys.iter().zip(counts.iter()).collect();
+    rows.sort_unstable();
+    assert_eq!(
+        rows,
+        vec![
+            (Some("a"), Some(3)),
+            (Some("b"), Some(1)),
+            (Some("c"), Some(1))
+        ]
+    );
+    assert_eq!(counts.null_count(), 0);
+}
+
+#[test]
+fn display_bar_chart() {
+    let hist: Histogram<String> = "the cat and the dog and the end"
+        .split_whitespace()
+        .filter(|w| *w != "end")
+        .collect();
+
+    assert_eq!(
+        hist.render(6, 2),
+        "the  3  ######\nand  2  ####\n… and 2 more\n"
+    );
+    let all = hist.render(6, usize::MAX);
+    assert!(all.starts_with("the  3  ######\nand  2  ####\n"));
+    assert_eq!(all.lines().count(), 4);
+    assert_eq!(hist.to_string().lines().count(), 4);
+    assert!(hist
+        .to_string()
+        .starts_with(&format!("the  3  {}\n", "#".repeat(40))));
+    assert_eq!(Histogram::<String>::new().to_string(), "");
+}
+
+#[test]
+fn generic_extend() {
+    fn extend_twice<T, E: Extend<T>>(target: &mut E, items: &[T])
+    where
+        T: Clone,
+    {
+        target.extend(items.iter().cloned());
+        target.extend(items.iter().cloned());
+    }
+
+    let mut by_ref = Histogram::<String>::new();
+    extend_twice(&mut by_ref, &["a", "b"]);
+    assert_eq!(by_ref.count("a"), 2);
+
+    let mut by_pair = Histogram::<char>::new();
+    extend_twice(&mut by_pair, &[('x', 3), ('y', 1)]);
+    assert_eq!(by_pair.count(&'x'), 6);
+    assert_eq!(by_pair.num_instances(), 8);
+}
+
+#[test]
+fn frequencies() {
+    let hist: Histogram<String> = "a b a c a b".split_whitespace().collect();
+    let freq = hist.frequencies();
+
+    assert_eq!(freq.len(), 3);
+    assert_eq!(freq.prob("a"), 0.5);
+    assert_eq!(freq.prob("missing"), 0.0);
+    assert!((freq.iter().map(|(_, p)| p).sum::<f64>() - 1.0).abs() < 1e-12);
+    assert_eq!(freq.top_k(1), vec![(&"a".to_string(), 0.5)]);
+    assert!((freq.entropy() - hist.entropy()).abs() < 1e-12);
+
+    let empty = Histogram::<String>::new();
+    assert!(empty.frequencies().is_empty());
+    assert_eq!(empty.frequencies().prob("a"), 0.0);
+}
+
+#[test]
+fn bucketing() {
+    use histongram::{Bucket, BucketedHistogram};
+
+    let mut hist = BucketedHistogram::<f64>::new(vec![0.0, 1.5]);
+    hist.extend([-3.0, 0.0, 1.0, 1.5, 9.0, f64::NAN]);
+    let counts: Vec<_> = hist.buckets().map(|(_, cnt)| cnt).collect();
+    assert_eq!(counts, vec![1, 2, 2]);
+    assert_eq!(hist.num_instances(), 5);
+    assert_eq!(
+        hist.bucket(&1.0),
+        Some(Bucket {
+            start: Some(0.0),
+            end: Some(1.5)
+        })
+    );
+    assert_eq!(hist.bucket(&f64::NAN), None);
+
+    let lengths = Histogram::<_>::from_owned_iter([1_u32, 2, 2, 7, 30]);
+    let buckets = lengths.bucketize(vec![5, 10]);
+    let labels: Vec<_> = buckets
+        .buckets()
+        .map(|(bucket, cnt)| format!("{bucket}={cnt}"))
+        .collect();
+    assert_eq!(labels, ["< 5=3", "[5, 10)=1", ">= 10=1"]);
+
+    // Invalid counts are skipped, overflows follow the policy
+    let mut weighted = BucketedHistogram::<f64, f64>::new(vec![0.0]);
+    weighted.add_n(1.0, 0.5);
+    weighted.add_n(1.0, f64::NAN);
+    weighted.add_n(1.0, -1.0);
+    assert_eq!(weighted.count(&1.0), 0.5);
+    let mut bytes = BucketedHistogram::<u32, u8>::new(vec![10]);
+    bytes.set_overflow_policy(OverflowPolicy::Saturate);
+    bytes.add_n(1, 200);
+    bytes.add_n(2, 200);
+    assert_eq!(bytes.count(&3), u8::MAX);
+}
+
+#[test]
+fn ordered_histogram() {
+    use std::hash::BuildHasher;
+    use std::ops::Bound;
+
+    use histongram::OrderedHistogram;
+
+    let mut words: OrderedHistogram<String> = "pear apple fig apple banana fig apple"
+        .split_whitespace()
+        .collect();
+    words.add_ref("cherry");
+
+    let sorted: Vec<_> = words.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(sorted, ["apple", "banana", "cherry", "fig", "pear"]);
+    assert_eq!(words.count("apple"), 3);
+    let b_to_g = (Bound::Included("b"), Bound::Excluded("g"));
+    assert_eq!(words.range_count::<str, _>(b_to_g), 4);
+    assert_eq!(words.last(), Some((&"pear".to_string(), 1)));
+
+    let hist: Histogram<String> = words.clone().into_histogram();
+    let back = OrderedHistogram::from(hist);
+    assert_eq!(back, words);
+
+    // Only the counts matter, not the overflow policy
+    let mut saturating = words.clone();
+    saturating.set_overflow_policy(OverflowPolicy::Saturate);
+    assert_eq!(saturating, words);
+    let state = RandomState::new();
+    assert_eq!(state.hash_one(&saturating), state.hash_one(&words));
+}
+
+#[test]
+fn small_histogram() {
+    use histongram::SmallHistogram;
+
+    let tags = ["NOUN", "VERB", "DET", "NOUN", "ADJ", "NOUN", "VERB"];
+    let small: SmallHistogram<String> = tags.into_iter().collect();
+    let hist: Histogram<String> = tags.into_iter().collect();
+
+    assert_eq!(small.num_categories(), hist.num_categories());
+    assert_eq!(small.num_instances(), hist.num_instances());
+    for (key, cnt) in small.iter() {
+        assert_eq!(hist.count(key), cnt);
+    }
+    let keys: Vec<_> = small.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, ["ADJ", "DET", "NOUN", "VERB"]);
+    assert_eq!(small.count("PRON"), 0);
+    assert_eq!(small.count_rel("NOUN"), hist.count_rel("NOUN"));
+    for (key, rel) in small.iter_rel() {
+        assert_eq!(hist.count_rel(key), rel);
+    }
+
+    let top: Vec<_> = small
+        .top_k(2)
+        .into_iter()
+        .map(|(key, cnt)| (key.as_str(), cnt))
+        .collect();
+    assert_eq!(top, [("NOUN", 3), ("VERB", 2)]);
+
+    let converted: Histogram<String> = small.clone().into_histogram();
+    assert_eq!(converted.into_std_hash_map(), hist.into_std_hash_map());
+
+    let mut small = small;
+    assert_eq!(small.remove("VERB"), 2);
+    assert_eq!(small.remove("VERB"), 0);
+    small.retain(|key, _cnt| key != "DET");
+    assert_eq!(
+        small.sorted_occurrences(),
+        [("NOUN".to_string(), 3), ("ADJ".to_string(), 1)]
+    );
+}
+
+#[test]
+fn streaming_from_bufread() {
+    use std::io::{BufReader, Cursor};
+
+    let text = include_str!("../LICENSE-APACHE");
+    let reader = BufReader::with_capacity(16, Cursor::new(text));
+    let words = Histogram::<String>::from_words(reader).unwrap();
+    let expected: Histogram<String> = text.split_whitespace().collect();
+    assert_eq!(words.into_std_hash_map(), expected.into_std_hash_map());
+
+    let lines = Histogram::<String>::from_lines(Cursor::new("a\r\nb\n\na")).unwrap();
+    assert_eq!(lines.count("a"), 2);
+    assert_eq!(lines.count(""), 1);
+    assert_eq!(lines.num_instances(), 4);
+
+    assert!(Histogram::<String>::from_lines(&b"\xff\n"[..]).is_err());
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn reservoir_sampling() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use histongram::{CountAndSample, ReservoirSampler};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut hits = Histogram::<usize>::new();
+    for _ in 0..2000 {
+        let mut sampler = ReservoirSampler::new(2);
+        for i in 0..10 {
+            sampler.add(i, &mut rng);
+        }
+        assert_eq!(sampler.sample().len(), 2);
+        hits.extend_from_owned(sampler.into_sample());
+    }
+    // Every instance should be kept about 2000 * 2 / 10 = 400 times
+    for i in 0..10 {
+        assert!(
+            (300..500).contains(&hits.count(&i)),
+            "{i}: {}",
+            hits.count(&i)
+        );
+    }
+
+    let mut words = CountAndSample::<String, _>::new(3, StdRng::seed_from_u64(5));
+    for word in "a b a c a d".split(' ') {
+        words.add_ref(word);
+    }
+    let (hist, sample) = words.into_parts();
+    assert_eq!(hist.count("a"), 3);
+    assert_eq!(sample.len(), 3);
+    assert!(sample.iter().all(|word| hist.contains_key(word)));
+
+    // Only new keys that are also sampled need a second copy
+    #[derive(Debug, PartialEq, Eq, Hash)]
+    struct Counted(u8);
+    static CLONES: AtomicUsize = AtomicUsize::new(0);
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            CLONES.fetch_add(1, Ordering::Relaxed);
+            Self(self.0)
+        }
+    }
+    let mut counted = CountAndSample::new(1, StdRng::seed_from_u64(5));
+    for _ in 0..100 {
+        counted.add_owned(Counted(1));
+    }
+    assert_eq!(counted.histogram().count(&Counted(1)), 100);
+    assert_eq!(counted.sample(), [Counted(1)]);
+    assert_eq!(CLONES.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn diff_between_versions() {
+    let old: Histogram<String> = "the cat sat on the mat".split(' ').collect();
+    let new: Histogram<String> = "the cat sat on the hat the end".split(' ').collect();
+
+    let diff = old.diff(&new);
+    let mut added: Vec<_> = diff.added.iter().map(|(key, _)| key.as_str()).collect();
+    added.sort_unstable();
+    assert_eq!(added, ["end", "hat"]);
+    assert_eq!(diff.removed, vec![(&"mat".to_string(), 1)]);
+    assert_eq!(diff.changed, vec![(&"the".to_string(), 2, 3)]);
+    assert_eq!(diff.unchanged, 3);
+    assert_eq!((diff.old_instances, diff.new_instances), (6, 8));
+
+    assert!(old.diff(&old).is_empty());
+}
+
+#[cfg(feature = "proptest")]
+proptest::proptest! {
+    #[test]
+    fn arbitrary_histograms_are_valid(hist in proptest::arbitrary::any::<Histogram<String>>()) {
+        proptest::prop_assert_eq!(hist.num_instances(), hist.counts().sum::<usize>());
+        proptest::prop_assert!(hist.counts().all(|cnt| cnt > 0));
+        proptest::prop_assert!(hist.num_categories() <= 64);
+    }
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn rkyv_zero_copy() {
+    use histongram::ArchivedHistogram;
+    use rkyv::rancor::Error;
+
+    let text = include_str!("../LICENSE-APACHE");
+    let hist: Histogram<String> = text.split_whitespace().collect();
+
+    let bytes = rkyv::to_bytes::<Error>(&hist).unwrap();
+    let archived =
+        rkyv::access::<ArchivedHistogram<String, DefaultHashBuilder, usize>, Error>(&bytes)
+            .unwrap();
+    assert_eq!(archived.num_categories(), hist.num_categories());
+    for (key, cnt) in &hist {
+        assert_eq!(
+            archived.get(key.as_str()).map(|c| c.to_native() as usize),
+            Some(cnt)
+        );
+    }
+    assert!(!archived.contains_key("not in there"));
+
+    let loaded: Histogram<String> = rkyv::deserialize::<_, Error>(archived).unwrap();
+    assert_eq!(loaded.into_std_hash_map(), hist.into_std_hash_map());
+}
+
+#[test]
+fn conditional_counts() {
+    use histongram::ConditionalHistogram;
+
+    let mut by_pos = ConditionalHistogram::<&str, String>::new();
+    for (tag, word) in [
+        ("DET", "the"),
+        ("NOUN", "cat"),
+        ("DET", "a"),
+        ("DET", "the"),
+    ] {
+        by_pos.add_owned(tag, word.to_string());
+    }
+
+    assert_eq!(by_pos.num_contexts(), 2);
+    assert_eq!(by_pos.num_instances(), 4);
+    assert_eq!(by_pos.count(&"DET", "the"), 2);
+    assert!((by_pos.prob(&"DET", "the") - 2.0 / 3.0).abs() < 1e-12);
+    assert_eq!(by_pos.prob(&"VERB", "the"), 0.0);
+    assert_eq!(by_pos.context_counts().count(&"DET"), 3);
+
+    let marginal = by_pos.marginal();
+    assert_eq!(marginal.num_instances(), 4);
+    assert_eq!(marginal.count("the"), 2);
+}
+
+#[test]
+fn percentiles() {
+    let hist = Histogram::<_>::from_counts([("a", 1), ("b", 1), ("c", 2), ("d", 10)]);
+
+    assert_eq!(hist.count_percentile(0.25), Some(1));
+    assert_eq!(hist.count_percentile(0.75), Some(2));
+    assert_eq!(hist.count_percentile(2.0), Some(10));
+    assert_eq!(hist.instances_percentile(0.25), Some(2));
+    assert_eq!(hist.instances_percentile(0.5), Some(10));
+    assert_eq!(hist.instances_percentile(-1.0), Some(1));
+
+    let empty = Histogram::<&str>::new();
+    assert_eq!(empty.count_percentile(0.5), None);
+    assert_eq!(empty.instances_percentile(0.5), None);
+}
+
+#[test]
+fn approximate_equality() {
+    let exact = Histogram::<_>::from_counts([("a", 1), ("b", 3)]);
+    let scaled = Histogram::<_>::from_counts([("a", 250), ("b", 750)]);
+    let off = Histogram::<_>::from_counts([("a", 251), ("b", 749)]);
+
+    assert_eq!(exact, Histogram::from_counts([("b", 3), ("a", 1)]));
+    assert_ne!(exact, scaled);
+    // Only the counts matter, not the overflow policy
+    let mut saturating = exact.clone();
+    saturating.set_overflow_policy(OverflowPolicy::Saturate);
+    assert_eq!(exact, saturating);
+    assert!(exact.approx_eq(&scaled, 0.0));
+    assert!(exact.approx_eq(&off, 0.01));
+    assert!(!exact.approx_eq(&off, 0.0001));
+    assert!(!exact.approx_eq(&Histogram::new(), 1.0));
+    assert!(Histogram::<&str>::new().approx_eq(&Histogram::<&str>::new(), 0.0));
+}
+
+#[test]
+fn lazily_sorted_iteration() {
+    let hist = Histogram::<_>::from_owned_iter("mississippi".chars());
+
+    let borrowed: Vec<_> = hist.sorted_iter().collect();
+    assert_eq!(borrowed, vec![(&'i', 4), (&'s', 4), (&'p', 2), (&'m', 1)]);
+
+    let owned: Vec<_> = hist.clone().into_sorted_iter().collect();
+    assert_eq!(
+        owned,
+        hist.sorted_occurrences_in(histongram::SortOrder::CountDescending)
+    );
+}
+
+#[test]
+fn annotated_histogram() {
+    use histongram::AnnotatedHistogram;
+
+    let mut first_line = AnnotatedHistogram::<String, usize>::new();
+    for (line, text) in ["a b", "b c", "a c c"].into_iter().enumerate() {
+        for word in text.split(' ') {
+            first_line.add_ref(word, || line);
+        }
+    }
+    assert_eq!(first_line.get("c"), Some((3, &1)));
+    assert_eq!(first_line.num_instances(), 7);
+
+    let mut later = AnnotatedHistogram::<String, usize>::new();
+    later.add_owned("a".to_string(), || 10);
+    later.add_owned("d".to_string(), || 11);
+    first_line.append(later, |ours, theirs| *ours = (*ours).min(theirs));
+    assert_eq!(first_line.get("a"), Some((3, &0)));
+    assert_eq!(first_line.metadata("d"), Some(&11));
+
+    first_line.retain(|_key, _cnt, &line| line < 10);
+    assert_eq!(first_line.metadata("d"), None);
+    assert_eq!(first_line.remove("b"), Some((2, 0)));
+
+    let hist = first_line.into_histogram();
+    assert_eq!(hist.num_categories(), 2);
+    assert_eq!(hist.count("c"), 3);
+}
+
+#[test]
+fn scaling_and_normalization() {
+    let reference =
+        Histogram::<_>::from_counts([("the", 600), ("cat", 300), ("dog", 99), ("gnu", 1)]);
+
+    let scaled = reference.clone().scale(0.1);
+    assert!((scaled.count("the") - 60.0).abs() < 1e-9);
+    assert!(reference.clone().scale(0.0).is_empty());
+
+    let matched = reference.clone().normalize_to(10.0);
+    assert!((matched.num_instances() - 10.0).abs() < 1e-9);
+    assert!(Histogram::<&str>::new().normalize_to(10.0).is_empty());
+
+    let downsampled = matched.round_to_counts();
+    assert_eq!(downsampled.num_instances(), 10);
+    assert_eq!(downsampled.count("the"), 6);
+    assert_eq!(downsampled.count("cat"), 3);
+    assert_eq!(downsampled.count("dog"), 1);
+    assert!(!downsampled.contains_key("gnu"));
+}
+
+#[test]
+fn wilson_confidence_intervals() {
+    let hist = Histogram::<_>::from_counts([("a", 1), ("b", 19)]);
+
+    let rare = hist.count_rel_interval("a", 1.96).unwrap();
+    assert!(rare.contains(0.05));
+    assert!(rare.lower > 0.0 && rare.upper < 0.3);
+
+    let absent = hist.count_rel_interval("c", 1.96).unwrap();
+    assert_eq!(absent.lower, 0.0);
+    assert!(absent.upper > 0.0);
+    assert!(absent.overlaps(rare));
+
+    assert_eq!(hist.iter_rel_intervals(1.96).count(), 2);
+    assert!(Histogram::<&str>::new()
+        .count_rel_interval("a", 1.96)
+        .is_none());
+}
+
+#[test]
+fn from_counts_sums_duplicates() {
+    let hist = Histogram::<_>::from_counts([("a", 1), ("b", 2), ("a", 3), ("c", 0)]);
+    assert_eq!(hist.count("a"), 4);
+    assert_eq!(hist.num_categories(), 2);
+    assert_eq!(hist.num_instances(), 6);
+    hist.debug_validate();
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic = "not positive"]
+fn debug_validate_catches_zero_counts() {
+    let hist: Histogram<_> = hashbrown::HashMap::from_iter([("a", 1), ("b", 0)]).into();
+    hist.debug_validate();
+}
+
+#[test]
+fn partition_by_predicate() {
+    use histongram::OverflowPolicy;
+
+    let mut hist = Histogram::<_>::from_owned_iter("aaabbc".chars());
+    hist.set_overflow_policy(OverflowPolicy::Saturate);
+
+    let (frequent, rare) = hist.partition(|_key, cnt| cnt >= 2);
+    assert_eq!(frequent.num_instances(), 5);
+    assert_eq!(rare.count(&'c'), 1);
+    assert!(!rare.contains_key(&'a'));
+    assert_eq!(frequent.overflow_policy(), OverflowPolicy::Saturate);
+    assert_eq!(rare.overflow_policy(), OverflowPolicy::Saturate);
+}
+
+#[test]
+#[cfg(feature = "unicode-segmentation")]
+fn unicode_segmentation() {
+    let mut graphemes = Histogram::<String>::new();
+    graphemes.count_graphemes("a\u{308}a\u{308}o");
+    assert_eq!(graphemes.count("a\u{308}"), 2);
+    assert_eq!(graphemes.num_categories(), 2);
+
+    let mut words = Histogram::<String>::new();
+    words.count_unicode_words("\"Hello,\" she said. Hello!");
+    assert_eq!(words.count("Hello"), 2);
+    assert_eq!(words.num_instances(), 4);
+}
+
+#[test]
+fn corpus_tf_idf() {
+    use histongram::Corpus;
+
+    let mut corpus = Corpus::<String>::new();
+    let first = corpus.add_document(["a", "a", "b"].into_iter().collect());
+    let second = corpus.add_document(["b", "c"].into_iter().collect());
+
+    assert_eq!(corpus.num_documents(), 2);
+    assert_eq!(corpus.document_frequency("b"), 2);
+    assert_eq!(corpus.idf("b"), 0.0);
+    assert_eq!(corpus.idf("unknown"), 0.0);
+    assert!((corpus.tf_idf(first, "a") - 2.0 / 3.0 * 2.0_f64.ln()).abs() < 1e-12);
+    assert_eq!(corpus.tf_idf(7, "a"), 0.0);
+
+    let top = corpus.top_tf_idf(second, 5);
+    assert_eq!(top.len(), 2);
+    assert_eq!(top[0].0, "c");
+    assert!(corpus.top_tf_idf(7, 5).is_empty());
+}
+
+#[test]
+fn tokenizers() {
+    use histongram::{Tokenizer, Whitespace};
+
+    struct Chars;
+    impl Tokenizer for Chars {
+        fn tokens<'a>(&self, text: &'a str) -> impl Iterator<Item = &'a str> {
+            text.char_indices()
+                .map(move |(idx, c)| &text[idx..idx + c.len_utf8()])
+        }
+    }
+
+    let mut words = Histogram::<String>::new();
+    words.count_text(" a  b\ta\n", &Whitespace);
+    assert_eq!(words.count("a"), 2);
+    assert_eq!(words.num_instances(), 3);
+
+    let mut chars = Histogram::<String>::new();
+    chars.count_text("aöa", &Chars);
+    assert_eq!(chars.count("ö"), 1);
+    assert_eq!(chars.count("a"), 2);
+
+    #[cfg(feature = "unicode-segmentation")]
+    {
+        let mut unicode = Histogram::<String>::new();
+        unicode.count_text("Hello, world!", &histongram::UnicodeWords);
+        assert_eq!(unicode.count("world"), 1);
+    }
+}
+
+#[test]
+fn byte_pair_encoding() {
+    use histongram::BytePairEncoding;
+
+    let words = Histogram::<_>::from_counts([
+        ("hug", 10),
+        ("pug", 5),
+        ("pun", 12),
+        ("bun", 4),
+        ("hugs", 5),
+    ]);
+    let bpe = BytePairEncoding::train(&words, 3);
+    assert_eq!(
+        bpe.merges(),
+        [
+            ("u".to_string(), "g".to_string()),
+            ("u".to_string(), "n".to_string()),
+            ("h".to_string(), "ug".to_string()),
+        ]
+    );
+    assert_eq!(bpe.split_word("hugs"), ["hug", "s"]);
+    assert_eq!(bpe.split_word("bug"), ["b", "ug"]);
+    assert_eq!(bpe.split_word("ünd"), ["ü", "n", "d"]);
+
+    // Training stops once every word is a single symbol
+    let all = BytePairEncoding::train(&words, 100);
+    assert!(all.merges().len() < 100);
+    assert_eq!(all.split_word("hugs"), ["hugs"]);
+
+    let restored = BytePairEncoding::from_merges(bpe.merges().to_vec());
+    assert_eq!(restored, bpe);
+}
+
+#[test]
+fn language_identification() {
+    use histongram::{LanguageIdentifier, LanguageProfile};
+
+    let profile = LanguageProfile::from_text("aab", 2, 3);
+    assert_eq!(profile.ngrams(), ["_", "a", "_a"]);
+    assert_eq!(profile.distance(&profile), 0);
+    assert_eq!(
+        profile.distance(&LanguageProfile::from(vec!["x".to_string()])),
+        3
+    );
+
+    let mut langid = LanguageIdentifier::new(3, 100);
+    assert_eq!(langid.classify("anything"), None);
+
+    langid.add_language("nl", "de kat zit op de mat en de hond ligt in het gras");
+    langid.add_profile(
+        "en",
+        LanguageProfile::from_text(
+            "the cat sits on the mat and the dog lies in the grass",
+            3,
+            100,
+        ),
+    );
+    assert_eq!(langid.classify("the dog and the cat"), Some(&"en"));
+    assert_eq!(langid.classify("de hond en de kat"), Some(&"nl"));
+
+    let ranked = langid.rank("de hond en de kat");
+    assert_eq!(ranked.len(), 2);
+    assert!(ranked[0].1 < ranked[1].1);
+}
+
+#[test]
+fn spelling_correction() {
+    use histongram::SpellChecker;
+
+    let words: Histogram<String> = "spelling spelling spelled spells correction corrections"
+        .split(' ')
+        .collect();
+    let checker = SpellChecker::new(&words);
+
+    // Known words stay as they are
+    assert_eq!(
+        checker.correct("spells").map(String::as_str),
+        Some("spells")
+    );
+    // Deletion, insertion, replacement and transposition
+    assert_eq!(
+        checker.correct("speling").map(String::as_str),
+        Some("spelling")
+    );
+    assert_eq!(
+        checker.correct("corection").map(String::as_str),
+        Some("correction")
+    );
+    assert_eq!(
+        checker.correct("spelluing").map(String::as_str),
+        Some("spelling")
+    );
+    assert_eq!(
+        checker.correct("spleled").map(String::as_str),
+        Some("spelled")
+    );
+    // Edit distance two
+    assert_eq!(
+        checker.correct("spelin").map(String::as_str),
+        Some("spelling")
+    );
+    assert_eq!(checker.correct("unrelated"), None);
+
+    let suggestions: Vec<_> = checker
+        .suggestions("spellx", 3)
+        .into_iter()
+        .map(|(word, cnt)| (word.as_str(), cnt))
+        .collect();
+    assert_eq!(suggestions, [("spells", 1)]);
+    assert!(checker.suggestions("spellx", 0).is_empty());
+
+    // Without `é` in the alphabet it can not be inserted, but known words with it still match
+    let words: Histogram<String> = ["café", "cafés"].into_iter().collect();
+    assert_eq!(
+        SpellChecker::new(&words).correct("caf").map(String::as_str),
+        Some("café")
+    );
+    let ascii = SpellChecker::with_alphabet(&words, 'a'..='z');
+    assert_eq!(ascii.correct("caf"), None);
+    assert_eq!(ascii.correct("cafés").map(String::as_str), Some("cafés"));
+    assert_eq!(ascii.correct("caféx").map(String::as_str), Some("café"));
+}
+
+#[test]
+fn byte_ngrams() {
+    let mut hist = Histogram::<[u8; 3]>::new();
+
+    hist.count_byte_ngrams(b"\x89PNG\r\n");
+    hist.count_byte_ngrams(b"\x89PN");
+    hist.count_byte_ngrams(b"PN");
+    assert_eq!(hist.count(&[0x89, b'P', b'N']), 2);
+    assert_eq!(hist.count(b"NG\r"), 1);
+    assert_eq!(hist.num_categories(), 4);
+    assert_eq!(hist.num_instances(), 5);
+
+    let mut single = Histogram::<[u8; 1]>::new();
+    single.count_byte_ngrams(&[0, 0, 255]);
+    assert_eq!(single.count(&[0]), 2);
+    assert_eq!(single.count(&[255]), 1);
+}